@@ -12,8 +12,15 @@ let
       SEARXNG_MCP_TOOLS = csv cfg.tools;
     }
     // lib.optionalAttrs (cfg.searxng.baseUrl != null) {
-      SEARXNG_BASE_URL = cfg.searxng.baseUrl;
+      SEARXNG_BASE_URL =
+        if builtins.isList cfg.searxng.baseUrl
+        then csv cfg.searxng.baseUrl
+        else cfg.searxng.baseUrl;
     }
+    // lib.optionalAttrs (cfg.searxng.strategy != null) {
+      SEARXNG_STRATEGY = cfg.searxng.strategy;
+    }
+    // nullableEnv cfg.searxng.ejectSecs "SEARXNG_EJECT_SECS"
     // lib.optionalAttrs (cfg.searxng.defaultCategories != []) {
       SEARXNG_DEFAULT_CATEGORIES = csv cfg.searxng.defaultCategories;
     }
@@ -26,12 +33,25 @@ let
     // nullableEnv cfg.searxng.safeSearch "SEARXNG_SAFE_SEARCH"
     // nullableEnv cfg.searxng.numResults "SEARXNG_NUM_RESULTS"
     // nullableEnv cfg.searxng.timeoutSecs "SEARXNG_TIMEOUT_SECS"
+    // nullableBoolEnv cfg.searxng.aggregatePages "SEARXNG_AGGREGATE_PAGES"
+    // nullableEnv cfg.searxng.maxPages "SEARXNG_MAX_PAGES"
+    // nullableEnv cfg.searxng.cacheTtlSecs "SEARXNG_CACHE_TTL_SECS"
+    // nullableEnv cfg.searxng.cacheMaxEntries "SEARXNG_CACHE_MAX_ENTRIES"
+    // nullableEnv cfg.searxng.retryMaxAttempts "SEARXNG_RETRY_MAX_ATTEMPTS"
+    // nullableEnv cfg.searxng.retryBaseDelayMs "SEARXNG_RETRY_BASE_DELAY_MS"
+    // nullableEnv cfg.searxng.retryMaxDelayMs "SEARXNG_RETRY_MAX_DELAY_MS"
+    // nullableEnv cfg.searxng.breakerThreshold "SEARXNG_BREAKER_THRESHOLD"
+    // nullableEnv cfg.searxng.breakerCooldownSecs "SEARXNG_BREAKER_COOLDOWN_SECS"
     // lib.optionalAttrs (cfg.browse.backend != null) {
       BROWSE_BACKEND = cfg.browse.backend;
     }
+    // lib.optionalAttrs (cfg.browse.extract != null) {
+      BROWSE_EXTRACT = cfg.browse.extract;
+    }
     // nullableBoolEnv cfg.browse.followRedirects "BROWSE_FOLLOW_REDIRECTS"
     // nullableEnv cfg.browse.maxRedirects "BROWSE_MAX_REDIRECTS"
     // nullableEnv cfg.browse.maxBytes "BROWSE_MAX_BYTES"
+    // nullableEnv cfg.browse.maxChars "BROWSE_MAX_CHARS"
     // nullableEnv cfg.browse.timeoutSecs "BROWSE_TIMEOUT_SECS"
     // lib.optionalAttrs (cfg.browse.userAgent != null) {
       BROWSE_USER_AGENT = cfg.browse.userAgent;
@@ -39,11 +59,37 @@ let
     // lib.optionalAttrs (cfg.browse.allowedHosts != []) {
       BROWSE_ALLOWED_HOSTS = csv cfg.browse.allowedHosts;
     }
+    // lib.optionalAttrs (cfg.browse.deniedHosts != []) {
+      BROWSE_DENIED_HOSTS = csv cfg.browse.deniedHosts;
+    }
     // nullableBoolEnv cfg.browse.allowPrivate "BROWSE_ALLOW_PRIVATE"
+    // lib.optionalAttrs (cfg.browse.denyCidrs != []) {
+      BROWSE_DENY_CIDRS = csv cfg.browse.denyCidrs;
+    }
+    // lib.optionalAttrs (cfg.browse.allowCidrs != []) {
+      BROWSE_ALLOW_CIDRS = csv cfg.browse.allowCidrs;
+    }
+    // nullableBoolEnv cfg.browse.respectRobots "BROWSE_RESPECT_ROBOTS"
+    // nullableEnv cfg.browse.maxConcurrent "BROWSE_MAX_CONCURRENT"
+    // nullableEnv cfg.browse.hostIntervalMs "BROWSE_HOST_INTERVAL_MS"
+    // nullableEnv cfg.browse.hostBurst "BROWSE_HOST_BURST"
+    // nullableEnv cfg.browse.queueWaitMs "BROWSE_QUEUE_WAIT_MS"
+    // nullableEnv cfg.browse.manyConcurrency "BROWSE_MANY_CONCURRENCY"
+    // nullableEnv cfg.browse.manyMaxUrls "BROWSE_MANY_MAX_URLS"
+    // nullableEnv cfg.browse.manyDeadlineSecs "BROWSE_MANY_DEADLINE_SECS"
+    // nullableEnv cfg.browse.researchPages "BROWSE_RESEARCH_PAGES"
+    // nullableEnv cfg.browse.researchMaxChars "BROWSE_RESEARCH_MAX_CHARS"
+    // nullableEnv cfg.browse.cacheTtlSecs "BROWSE_CACHE_TTL_SECS"
+    // nullableEnv cfg.browse.cacheMaxEntries "BROWSE_CACHE_MAX_ENTRIES"
+    // lib.optionalAttrs (cfg.browse.diskCacheDir != null) {
+      BROWSE_DISK_CACHE_DIR = cfg.browse.diskCacheDir;
+    }
+    // nullableEnv cfg.browse.diskCacheMaxBytes "BROWSE_DISK_CACHE_MAX_BYTES"
     // lib.optionalAttrs (cfg.obscura.waitUntil != null) {
       BROWSE_OBSCURA_WAIT_UNTIL = cfg.obscura.waitUntil;
     }
     // nullableBoolEnv cfg.obscura.stealth "BROWSE_OBSCURA_STEALTH"
+    // nullableEnv cfg.obscura.maxConcurrent "BROWSE_OBSCURA_MAX_CONCURRENT"
     // nullableBoolEnv cfg.streamableHttp.statefulMode "STREAMABLE_HTTP_STATEFUL"
     // nullableEnv cfg.streamableHttp.sseKeepAliveSecs "STREAMABLE_HTTP_SSE_KEEP_ALIVE"
     // nullableEnv cfg.streamableHttp.sseRetrySecs "STREAMABLE_HTTP_SSE_RETRY";
@@ -87,9 +133,21 @@ in {
 
     searxng = {
       baseUrl = lib.mkOption {
-        type = lib.types.nullOr lib.types.str;
+        type = lib.types.nullOr (lib.types.either lib.types.str (lib.types.listOf lib.types.str));
         default = null;
-        description = "SearXNG base URL exported as SEARXNG_BASE_URL.";
+        description = "SearXNG base URL, or list of instance URLs, exported as SEARXNG_BASE_URL.";
+      };
+
+      strategy = lib.mkOption {
+        type = lib.types.nullOr (lib.types.enum ["failover" "roundrobin" "fanout"]);
+        default = null;
+        description = "Multi-instance strategy exported as SEARXNG_STRATEGY.";
+      };
+
+      ejectSecs = lib.mkOption {
+        type = lib.types.nullOr lib.types.ints.unsigned;
+        default = null;
+        description = "How long failing instances are skipped, exported as SEARXNG_EJECT_SECS.";
       };
 
       defaultCategories = lib.mkOption {
@@ -127,6 +185,60 @@ in {
         default = null;
         description = "SearXNG request timeout exported as SEARXNG_TIMEOUT_SECS.";
       };
+
+      aggregatePages = lib.mkOption {
+        type = lib.types.nullOr lib.types.bool;
+        default = null;
+        description = "Whether search merges successive pages by default via SEARXNG_AGGREGATE_PAGES.";
+      };
+
+      maxPages = lib.mkOption {
+        type = lib.types.nullOr lib.types.ints.positive;
+        default = null;
+        description = "Maximum pages fetched per aggregated search exported as SEARXNG_MAX_PAGES.";
+      };
+
+      cacheTtlSecs = lib.mkOption {
+        type = lib.types.nullOr lib.types.ints.unsigned;
+        default = null;
+        description = "Search cache TTL exported as SEARXNG_CACHE_TTL_SECS (0 disables the cache).";
+      };
+
+      cacheMaxEntries = lib.mkOption {
+        type = lib.types.nullOr lib.types.ints.unsigned;
+        default = null;
+        description = "Search cache capacity exported as SEARXNG_CACHE_MAX_ENTRIES.";
+      };
+
+      retryMaxAttempts = lib.mkOption {
+        type = lib.types.nullOr lib.types.ints.positive;
+        default = null;
+        description = "Attempts per SearXNG call (including the first) exported as SEARXNG_RETRY_MAX_ATTEMPTS.";
+      };
+
+      retryBaseDelayMs = lib.mkOption {
+        type = lib.types.nullOr lib.types.ints.unsigned;
+        default = null;
+        description = "Initial retry backoff exported as SEARXNG_RETRY_BASE_DELAY_MS.";
+      };
+
+      retryMaxDelayMs = lib.mkOption {
+        type = lib.types.nullOr lib.types.ints.unsigned;
+        default = null;
+        description = "Maximum retry backoff exported as SEARXNG_RETRY_MAX_DELAY_MS.";
+      };
+
+      breakerThreshold = lib.mkOption {
+        type = lib.types.nullOr lib.types.ints.unsigned;
+        default = null;
+        description = "Consecutive failures before the circuit breaker opens, exported as SEARXNG_BREAKER_THRESHOLD (0 disables it).";
+      };
+
+      breakerCooldownSecs = lib.mkOption {
+        type = lib.types.nullOr lib.types.ints.unsigned;
+        default = null;
+        description = "Circuit breaker cooldown exported as SEARXNG_BREAKER_COOLDOWN_SECS.";
+      };
     };
 
     browse = {
@@ -136,6 +248,12 @@ in {
         description = "Browse backend exported as BROWSE_BACKEND.";
       };
 
+      extract = lib.mkOption {
+        type = lib.types.nullOr (lib.types.enum ["full" "article"]);
+        default = null;
+        description = "Default browse content extraction mode exported as BROWSE_EXTRACT.";
+      };
+
       followRedirects = lib.mkOption {
         type = lib.types.nullOr lib.types.bool;
         default = null;
@@ -154,6 +272,12 @@ in {
         description = "Maximum fetched bytes exported as BROWSE_MAX_BYTES.";
       };
 
+      maxChars = lib.mkOption {
+        type = lib.types.nullOr lib.types.ints.positive;
+        default = null;
+        description = "Characters returned per browse call exported as BROWSE_MAX_CHARS.";
+      };
+
       timeoutSecs = lib.mkOption {
         type = lib.types.nullOr lib.types.ints.positive;
         default = null;
@@ -169,7 +293,15 @@ in {
       allowedHosts = lib.mkOption {
         type = lib.types.listOf lib.types.str;
         default = [];
-        description = "Allowed browse hostnames exported as BROWSE_ALLOWED_HOSTS.";
+        example = [ "docs.rs" "*.rust-lang.org" ".python.org" "localhost:8080" ];
+        description = "Allowed browse host patterns exported as BROWSE_ALLOWED_HOSTS.";
+      };
+
+      deniedHosts = lib.mkOption {
+        type = lib.types.listOf lib.types.str;
+        default = [];
+        example = [ ".doubleclick.net" ];
+        description = "Browse host patterns to refuse, exported as BROWSE_DENIED_HOSTS.";
       };
 
       allowPrivate = lib.mkOption {
@@ -177,6 +309,108 @@ in {
         default = null;
         description = "Whether private browse targets are allowed via BROWSE_ALLOW_PRIVATE.";
       };
+
+      denyCidrs = lib.mkOption {
+        type = lib.types.listOf lib.types.str;
+        default = [];
+        example = [ "203.0.113.0/24" ];
+        description = "Extra address ranges browse refuses, exported as BROWSE_DENY_CIDRS.";
+      };
+
+      allowCidrs = lib.mkOption {
+        type = lib.types.listOf lib.types.str;
+        default = [];
+        example = [ "10.20.0.0/16" ];
+        description = "Address ranges browse may reach despite the deny set, exported as BROWSE_ALLOW_CIDRS.";
+      };
+
+      respectRobots = lib.mkOption {
+        type = lib.types.nullOr lib.types.bool;
+        default = null;
+        description = "Whether browse honors robots.txt and Crawl-delay via BROWSE_RESPECT_ROBOTS.";
+      };
+
+      maxConcurrent = lib.mkOption {
+        type = lib.types.nullOr lib.types.ints.positive;
+        default = null;
+        description = "Concurrent simple-backend browses exported as BROWSE_MAX_CONCURRENT.";
+      };
+
+      hostIntervalMs = lib.mkOption {
+        type = lib.types.nullOr lib.types.ints.unsigned;
+        default = null;
+        description = "Per-host request interval exported as BROWSE_HOST_INTERVAL_MS (0 disables).";
+      };
+
+      hostBurst = lib.mkOption {
+        type = lib.types.nullOr lib.types.ints.positive;
+        default = null;
+        description = "Per-host request burst exported as BROWSE_HOST_BURST.";
+      };
+
+      queueWaitMs = lib.mkOption {
+        type = lib.types.nullOr lib.types.ints.unsigned;
+        default = null;
+        description = "Maximum browse queueing time exported as BROWSE_QUEUE_WAIT_MS.";
+      };
+
+      manyConcurrency = lib.mkOption {
+        type = lib.types.nullOr lib.types.ints.positive;
+        default = null;
+        description = "URLs fetched at once by one browse_many call, exported as BROWSE_MANY_CONCURRENCY.";
+      };
+
+      manyMaxUrls = lib.mkOption {
+        type = lib.types.nullOr lib.types.ints.unsigned;
+        default = null;
+        description = "Maximum URLs per browse_many call exported as BROWSE_MANY_MAX_URLS.";
+      };
+
+      manyDeadlineSecs = lib.mkOption {
+        type = lib.types.nullOr lib.types.ints.unsigned;
+        default = null;
+        description = "Overall browse_many time budget exported as BROWSE_MANY_DEADLINE_SECS.";
+      };
+
+      researchPages = lib.mkOption {
+        type = lib.types.nullOr lib.types.ints.unsigned;
+        default = null;
+        description = "Search results the research tool fetches by default, exported as BROWSE_RESEARCH_PAGES.";
+      };
+
+      researchMaxChars = lib.mkOption {
+        type = lib.types.nullOr lib.types.ints.positive;
+        default = null;
+        description = "Default research excerpt size per page exported as BROWSE_RESEARCH_MAX_CHARS.";
+      };
+
+      cacheTtlSecs = lib.mkOption {
+        type = lib.types.nullOr lib.types.ints.unsigned;
+        default = null;
+        description = "Browse cache TTL exported as BROWSE_CACHE_TTL_SECS (0 disables the cache).";
+      };
+
+      cacheMaxEntries = lib.mkOption {
+        type = lib.types.nullOr lib.types.ints.unsigned;
+        default = null;
+        description = "Browse cache capacity exported as BROWSE_CACHE_MAX_ENTRIES.";
+      };
+
+      diskCacheDir = lib.mkOption {
+        type = lib.types.nullOr lib.types.str;
+        default = null;
+        example = "/var/lib/searxng-mcp/browse-cache";
+        description = ''
+          Directory for the persistent browse cache, exported as BROWSE_DISK_CACHE_DIR.
+          It must be writable by the service, e.g. under its StateDirectory.
+        '';
+      };
+
+      diskCacheMaxBytes = lib.mkOption {
+        type = lib.types.nullOr lib.types.ints.positive;
+        default = null;
+        description = "Persistent browse cache size cap exported as BROWSE_DISK_CACHE_MAX_BYTES.";
+      };
     };
 
     obscura = {
@@ -191,6 +425,12 @@ in {
         default = null;
         description = "Whether to enable Obscura-only stealth mode via BROWSE_OBSCURA_STEALTH.";
       };
+
+      maxConcurrent = lib.mkOption {
+        type = lib.types.nullOr lib.types.ints.positive;
+        default = null;
+        description = "Concurrent Obscura browses exported as BROWSE_OBSCURA_MAX_CONCURRENT.";
+      };
     };
 
     streamableHttp = {
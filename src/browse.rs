@@ -1,16 +1,43 @@
 use std::net::IpAddr;
-#[cfg(feature = "obscura-backend")]
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use std::time::Duration;
 
 use anyhow::{Context, Result, anyhow};
+use ego_tree::iter::Edge;
 use futures_util::StreamExt;
-use regex::Regex;
+use ipnet::IpNet;
 use reqwest::Url;
+use scraper::{ElementRef, Html, Node, Selector};
+
+use crate::disk_cache::{CachedMeta, DiskCache};
+use crate::host_pattern::{HostPattern, parse_host_patterns};
+use crate::ip_policy::{IpPolicy, parse_cidrs};
+use crate::limits::BrowseLimits;
+use crate::links::{PageLink, extract_links, render_links};
+use crate::metadata::{PageMetadata, extract_metadata};
+use crate::resolver::{HostLookup, PinnedResolver, SystemLookup};
+use crate::robots::{Robots, RobotsRules};
 
 const DEFAULT_MAX_REDIRECTS: usize = 10;
 const DEFAULT_MAX_BYTES: usize = 2_000_000;
 const DEFAULT_TIMEOUT_SECS: u64 = 20;
+const DEFAULT_MAX_CHARS: usize = 50_000;
+const DEFAULT_CACHE_TTL_SECS: u64 = 300;
+const DEFAULT_CACHE_MAX_ENTRIES: usize = 64;
+const DEFAULT_DISK_CACHE_MAX_BYTES: u64 = 256 * 1024 * 1024;
+const DEFAULT_MAX_CONCURRENT: usize = 8;
+const DEFAULT_OBSCURA_MAX_CONCURRENT: usize = 2;
+const DEFAULT_HOST_INTERVAL_MS: u64 = 500;
+const DEFAULT_HOST_BURST: usize = 4;
+const DEFAULT_QUEUE_WAIT_MS: u64 = 10_000;
+const DEFAULT_MANY_CONCURRENCY: usize = 4;
+const DEFAULT_MANY_MAX_URLS: usize = 10;
+const DEFAULT_MANY_DEADLINE_SECS: u64 = 60;
+const DEFAULT_RESEARCH_PAGES: usize = 5;
+const DEFAULT_RESEARCH_MAX_CHARS: usize = 4_000;
+/// RFC 9309 asks crawlers to parse at least 500 KiB of robots.txt.
+const MAX_ROBOTS_BYTES: usize = 512 * 1024;
+const MAX_ROBOTS_REDIRECTS: usize = 5;
 
 fn env_bool(key: &str, default: bool) -> bool {
     match std::env::var(key) {
@@ -53,14 +80,7 @@ fn env_u64(key: &str) -> Option<u64> {
         .and_then(|v| v.trim().parse::<u64>().ok())
 }
 
-fn parse_csv(s: &str) -> Vec<String> {
-    s.split(',')
-        .map(|v| v.trim().to_ascii_lowercase())
-        .filter(|v| !v.is_empty())
-        .collect()
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum BrowseBackend {
     Simple,
     Obscura,
@@ -78,12 +98,85 @@ impl BrowseBackend {
     }
 }
 
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize, schemars::JsonSchema)]
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    schemars::JsonSchema,
+)]
 #[serde(rename_all = "lowercase")]
 pub enum BrowseFormat {
     #[default]
     Markdown,
     Text,
+    /// Every anchor on the page as an absolute URL.
+    Links,
+}
+
+impl BrowseFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Markdown => "markdown",
+            Self::Text => "text",
+            Self::Links => "links",
+        }
+    }
+}
+
+/// How much of the page to render.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    schemars::JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum BrowseExtract {
+    /// The whole document.
+    #[default]
+    Full,
+    /// Only the main content, with navigation, sidebars and footers dropped.
+    Article,
+}
+
+impl BrowseExtract {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Full => "full",
+            Self::Article => "article",
+        }
+    }
+
+    fn parse(value: &str) -> Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "full" => Ok(Self::Full),
+            "article" => Ok(Self::Article),
+            other => Err(anyhow!(
+                "invalid BROWSE_EXTRACT '{other}' (valid: full,article)"
+            )),
+        }
+    }
+}
+
+/// Per-call overrides of the configured browse defaults.
+#[derive(Debug, Clone, Default)]
+pub struct BrowseOptions {
+    pub format: Option<BrowseFormat>,
+    pub extract: Option<BrowseExtract>,
+    /// CSS selector restricting rendering to the matched elements.
+    pub selector: Option<String>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -112,15 +205,48 @@ impl ObscuraWaitUntil {
 pub struct BrowseConfig {
     pub backend: BrowseBackend,
     pub format: BrowseFormat,
+    pub extract: BrowseExtract,
     pub obscura_wait_until: ObscuraWaitUntil,
     pub obscura_stealth: bool,
     pub follow_redirects: bool,
     pub max_redirects: usize,
     pub max_bytes: usize,
+    /// Default size of one `browse` chunk, in characters.
+    pub max_chars: usize,
     pub timeout: Duration,
     pub user_agent: String,
-    pub allowed_hosts: Option<Vec<String>>,
+    /// `BROWSE_ALLOWED_HOSTS`; when set, only matching hosts may be browsed.
+    /// Their addresses still go through [`BrowseConfig::ip_policy`].
+    pub allowed_hosts: Option<Vec<HostPattern>>,
+    /// `BROWSE_DENIED_HOSTS`; refused whether or not an allowlist is set.
+    pub denied_hosts: Vec<HostPattern>,
     pub allow_private: bool,
+    /// Extra ranges to refuse, on top of the built-in deny set (`BROWSE_DENY_CIDRS`).
+    pub deny_cidrs: Vec<IpNet>,
+    /// Ranges to permit even if denied (`BROWSE_ALLOW_CIDRS`).
+    pub allow_cidrs: Vec<IpNet>,
+    pub cache_ttl: Duration,
+    pub cache_max_entries: usize,
+    /// Simple backend only; `None` unless `BROWSE_DISK_CACHE_DIR` is set.
+    pub disk_cache: Option<DiskCache>,
+    /// Set when `BROWSE_RESPECT_ROBOTS` is on; shared so rules and crawl delays
+    /// carry across calls.
+    pub robots: Option<Arc<Robots>>,
+    /// Concurrency and per-host rate limits, shared by all browse calls.
+    pub limits: Arc<BrowseLimits>,
+    /// How many URLs one `browse_many` call fetches at once.
+    pub many_concurrency: usize,
+    pub many_max_urls: usize,
+    /// Overall time budget of one `browse_many` or `research` call.
+    pub many_deadline: Duration,
+    /// How many search results `research` fetches by default.
+    pub research_pages: usize,
+    /// Default excerpt size per page in `research`, in characters.
+    pub research_max_chars: usize,
+    /// Name resolution for browse targets; replaced in tests.
+    pub lookup: Arc<dyn HostLookup>,
+    /// Pooled client for the simple backend, built on first use and shared by clones.
+    http: Arc<OnceLock<reqwest::Client>>,
 }
 
 impl Default for BrowseConfig {
@@ -128,15 +254,37 @@ impl Default for BrowseConfig {
         Self {
             backend: BrowseBackend::Simple,
             format: BrowseFormat::Markdown,
+            extract: BrowseExtract::Full,
             obscura_wait_until: ObscuraWaitUntil::Load,
             obscura_stealth: false,
             follow_redirects: false,
             max_redirects: DEFAULT_MAX_REDIRECTS,
             max_bytes: DEFAULT_MAX_BYTES,
+            max_chars: DEFAULT_MAX_CHARS,
             timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECS),
             user_agent: format!("searxng-mcp/{}", env!("CARGO_PKG_VERSION")),
             allowed_hosts: None,
+            denied_hosts: Vec::new(),
             allow_private: false,
+            deny_cidrs: Vec::new(),
+            allow_cidrs: Vec::new(),
+            cache_ttl: Duration::from_secs(DEFAULT_CACHE_TTL_SECS),
+            cache_max_entries: DEFAULT_CACHE_MAX_ENTRIES,
+            disk_cache: None,
+            robots: None,
+            limits: Arc::new(BrowseLimits::new(
+                DEFAULT_MAX_CONCURRENT,
+                Duration::from_millis(DEFAULT_HOST_INTERVAL_MS),
+                DEFAULT_HOST_BURST as u32,
+                Duration::from_millis(DEFAULT_QUEUE_WAIT_MS),
+            )),
+            many_concurrency: DEFAULT_MANY_CONCURRENCY,
+            many_max_urls: DEFAULT_MANY_MAX_URLS,
+            many_deadline: Duration::from_secs(DEFAULT_MANY_DEADLINE_SECS),
+            research_pages: DEFAULT_RESEARCH_PAGES,
+            research_max_chars: DEFAULT_RESEARCH_MAX_CHARS,
+            lookup: Arc::new(SystemLookup),
+            http: Arc::new(OnceLock::new()),
         }
     }
 }
@@ -150,6 +298,9 @@ impl BrowseConfig {
         {
             cfg.backend = BrowseBackend::parse(&v)?;
         }
+        if let Ok(v) = std::env::var("BROWSE_EXTRACT") {
+            cfg.extract = BrowseExtract::parse(&v)?;
+        }
         if let Ok(v) = std::env::var("BROWSE_OBSCURA_WAIT_UNTIL") {
             cfg.obscura_wait_until = ObscuraWaitUntil::parse(&v)?;
         }
@@ -164,6 +315,10 @@ impl BrowseConfig {
         cfg.follow_redirects = env_bool("BROWSE_FOLLOW_REDIRECTS", cfg.follow_redirects);
         cfg.max_redirects = env_usize("BROWSE_MAX_REDIRECTS", cfg.max_redirects);
         cfg.max_bytes = env_usize("BROWSE_MAX_BYTES", cfg.max_bytes);
+        cfg.max_chars = env_usize("BROWSE_MAX_CHARS", cfg.max_chars);
+        if cfg.max_chars == 0 {
+            return Err(anyhow!("BROWSE_MAX_CHARS must be greater than 0"));
+        }
         if let Some(secs) = env_u64("BROWSE_TIMEOUT_SECS") {
             cfg.timeout = Duration::from_secs(secs);
         }
@@ -173,53 +328,191 @@ impl BrowseConfig {
             cfg.user_agent = v;
         }
         if let Ok(v) = std::env::var("BROWSE_ALLOWED_HOSTS") {
-            let list = parse_csv(&v);
+            let list = parse_host_patterns("BROWSE_ALLOWED_HOSTS", &v)?;
             cfg.allowed_hosts = if list.is_empty() { None } else { Some(list) };
         }
+        if let Ok(v) = std::env::var("BROWSE_DENIED_HOSTS") {
+            cfg.denied_hosts = parse_host_patterns("BROWSE_DENIED_HOSTS", &v)?;
+        }
         cfg.allow_private = env_bool("BROWSE_ALLOW_PRIVATE", cfg.allow_private);
+        if let Ok(v) = std::env::var("BROWSE_DENY_CIDRS") {
+            cfg.deny_cidrs = parse_cidrs("BROWSE_DENY_CIDRS", &v)?;
+        }
+        if let Ok(v) = std::env::var("BROWSE_ALLOW_CIDRS") {
+            cfg.allow_cidrs = parse_cidrs("BROWSE_ALLOW_CIDRS", &v)?;
+        }
+        if let Some(secs) = env_u64("BROWSE_CACHE_TTL_SECS") {
+            cfg.cache_ttl = Duration::from_secs(secs);
+        }
+        cfg.cache_max_entries = env_usize("BROWSE_CACHE_MAX_ENTRIES", cfg.cache_max_entries);
+        if let Ok(dir) = std::env::var("BROWSE_DISK_CACHE_DIR")
+            && !dir.trim().is_empty()
+        {
+            let max_bytes =
+                env_u64("BROWSE_DISK_CACHE_MAX_BYTES").unwrap_or(DEFAULT_DISK_CACHE_MAX_BYTES);
+            cfg.disk_cache = Some(DiskCache::new(dir.trim().into(), max_bytes));
+        }
+        if env_bool("BROWSE_RESPECT_ROBOTS", false) {
+            cfg.robots = Some(Arc::new(Robots::new(&cfg.user_agent)));
+        }
+
+        // Obscura browses each hold a thread and a runtime, so they get their own, lower cap.
+        let (concurrency_var, max_concurrent) = match cfg.backend {
+            BrowseBackend::Simple => ("BROWSE_MAX_CONCURRENT", DEFAULT_MAX_CONCURRENT),
+            BrowseBackend::Obscura => (
+                "BROWSE_OBSCURA_MAX_CONCURRENT",
+                DEFAULT_OBSCURA_MAX_CONCURRENT,
+            ),
+        };
+        let max_concurrent = env_usize(concurrency_var, max_concurrent);
+        if max_concurrent == 0 {
+            return Err(anyhow!("{concurrency_var} must be greater than 0"));
+        }
+        let host_interval = env_u64("BROWSE_HOST_INTERVAL_MS").unwrap_or(DEFAULT_HOST_INTERVAL_MS);
+        let host_burst = env_usize("BROWSE_HOST_BURST", DEFAULT_HOST_BURST);
+        let queue_wait = env_u64("BROWSE_QUEUE_WAIT_MS").unwrap_or(DEFAULT_QUEUE_WAIT_MS);
+        cfg.limits = Arc::new(BrowseLimits::new(
+            max_concurrent,
+            Duration::from_millis(host_interval),
+            u32::try_from(host_burst).unwrap_or(u32::MAX),
+            Duration::from_millis(queue_wait),
+        ));
+
+        cfg.many_concurrency = env_usize("BROWSE_MANY_CONCURRENCY", cfg.many_concurrency);
+        if cfg.many_concurrency == 0 {
+            return Err(anyhow!("BROWSE_MANY_CONCURRENCY must be greater than 0"));
+        }
+        cfg.many_max_urls = env_usize("BROWSE_MANY_MAX_URLS", cfg.many_max_urls);
+        if let Some(secs) = env_u64("BROWSE_MANY_DEADLINE_SECS") {
+            cfg.many_deadline = Duration::from_secs(secs);
+        }
+        cfg.research_pages = env_usize("BROWSE_RESEARCH_PAGES", cfg.research_pages);
+        cfg.research_max_chars = env_usize("BROWSE_RESEARCH_MAX_CHARS", cfg.research_max_chars);
+        if cfg.research_max_chars == 0 {
+            return Err(anyhow!("BROWSE_RESEARCH_MAX_CHARS must be greater than 0"));
+        }
 
         Ok(cfg)
     }
+
+    /// The simple backend's HTTP client, reused across calls so connections,
+    /// TLS sessions and HTTP/2 streams are pooled per host.
+    ///
+    /// It never follows redirects: the simple backend follows them itself so
+    /// the SSRF checks apply to each hop.
+    pub fn http_client(&self) -> Result<&reqwest::Client> {
+        if let Some(http) = self.http.get() {
+            return Ok(http);
+        }
+        let http = self.new_http_client()?;
+        Ok(self.http.get_or_init(|| http))
+    }
+
+    /// A fresh, unshared client with the same settings as [`Self::http_client`],
+    /// including the pinned resolver.
+    pub fn new_http_client(&self) -> Result<reqwest::Client> {
+        let mut builder = reqwest::ClientBuilder::new()
+            .timeout(self.timeout)
+            .user_agent(self.user_agent.clone())
+            .redirect(reqwest::redirect::Policy::none());
+        if let Some(resolver) = self.pinned_resolver() {
+            builder = builder.dns_resolver(Arc::new(resolver));
+        }
+        builder.build().context("failed to build HTTP client")
+    }
+
+    /// The addresses browse may connect to. Only `BROWSE_ALLOW_PRIVATE` turns off
+    /// the built-in deny set (an allowlist does not); `BROWSE_DENY_CIDRS` always applies.
+    pub fn ip_policy(&self) -> IpPolicy {
+        IpPolicy::new(
+            !self.allow_private,
+            self.deny_cidrs.clone(),
+            self.allow_cidrs.clone(),
+        )
+    }
+
+    /// The resolver that keeps connections off denied addresses, unless the
+    /// policy denies nothing.
+    pub fn pinned_resolver(&self) -> Option<PinnedResolver> {
+        let policy = self.ip_policy();
+        if policy.is_permissive() {
+            return None;
+        }
+        Some(PinnedResolver::new(self.lookup.clone(), policy))
+    }
 }
 
+/// Elements whose content is never part of the readable page.
+fn is_non_content_element(name: &str) -> bool {
+    matches!(
+        name,
+        "script" | "style" | "noscript" | "template" | "svg" | "iframe" | "object" | "canvas"
+    )
+}
+
+fn is_block_element(name: &str) -> bool {
+    matches!(
+        name,
+        "address"
+            | "article"
+            | "aside"
+            | "blockquote"
+            | "br"
+            | "div"
+            | "dl"
+            | "dt"
+            | "dd"
+            | "fieldset"
+            | "figcaption"
+            | "figure"
+            | "footer"
+            | "form"
+            | "h1"
+            | "h2"
+            | "h3"
+            | "h4"
+            | "h5"
+            | "h6"
+            | "header"
+            | "hr"
+            | "li"
+            | "main"
+            | "nav"
+            | "ol"
+            | "p"
+            | "pre"
+            | "section"
+            | "table"
+            | "tbody"
+            | "td"
+            | "tfoot"
+            | "th"
+            | "thead"
+            | "title"
+            | "tr"
+            | "ul"
+    )
+}
+
+/// Drop comments and non-content elements, re-serializing the parsed document.
 fn strip_styles_and_scripts(html: &str) -> String {
-    let style_re = Regex::new(r"(?is)<style[^>]*>.*?</style>").unwrap();
-    let script_re = Regex::new(r"(?is)<script[^>]*>.*?</script>").unwrap();
-    let without_styles = style_re.replace_all(html, "");
-    let cleaned = script_re.replace_all(&without_styles, "");
-    cleaned.to_string()
-}
-
-fn ip_is_private(ip: IpAddr) -> bool {
-    match ip {
-        IpAddr::V4(v4) => {
-            let oct = v4.octets();
-            // 0.0.0.0/8, 10.0.0.0/8, 127.0.0.0/8, 169.254.0.0/16, 172.16.0.0/12,
-            // 192.168.0.0/16, 100.64.0.0/10 (CGNAT)
-            oct[0] == 0
-                || oct[0] == 10
-                || oct[0] == 127
-                || (oct[0] == 169 && oct[1] == 254)
-                || (oct[0] == 172 && (16..=31).contains(&oct[1]))
-                || (oct[0] == 192 && oct[1] == 168)
-                || (oct[0] == 100 && (64..=127).contains(&oct[1]))
-        }
-        IpAddr::V6(v6) => {
-            if v6.is_loopback() || v6.is_unspecified() {
-                return true;
-            }
-            let seg = v6.segments();
-            // fc00::/7 unique local
-            if (seg[0] & 0xfe00) == 0xfc00 {
-                return true;
-            }
-            // fe80::/10 link-local
-            if (seg[0] & 0xffc0) == 0xfe80 {
-                return true;
-            }
-            false
+    let mut doc = Html::parse_document(html);
+    let doomed: Vec<_> = doc
+        .tree
+        .nodes()
+        .filter(|node| match node.value() {
+            Node::Comment(_) => true,
+            Node::Element(el) => is_non_content_element(el.name()),
+            _ => false,
+        })
+        .map(|node| node.id())
+        .collect();
+    for id in doomed {
+        if let Some(mut node) = doc.tree.get_mut(id) {
+            node.detach();
         }
     }
+    doc.html()
 }
 
 fn host_is_obviously_local(host: &str) -> bool {
@@ -229,17 +522,28 @@ fn host_is_obviously_local(host: &str) -> bool {
 
 fn policy_allows_host(
     host: &str,
+    port: Option<u16>,
     allow_private: bool,
-    allowed_hosts: Option<&[String]>,
+    allowed_hosts: Option<&[HostPattern]>,
+    denied_hosts: &[HostPattern],
 ) -> Result<()> {
     let host_lc = host.to_ascii_lowercase();
 
-    // If an explicit allowlist is set, it fully defines what's allowed.
+    if let Some(rule) = denied_hosts.iter().find(|p| p.matches(&host_lc, port)) {
+        return Err(anyhow!(
+            "refusing to browse {host_lc}: it matches BROWSE_DENIED_HOSTS entry '{rule}'"
+        ));
+    }
+
+    // If an explicit allowlist is set, it fully defines which hosts are allowed;
+    // their addresses are still checked against the IP policy.
     if let Some(list) = allowed_hosts {
-        if list.iter().any(|h| h == &host_lc) {
+        if list.iter().any(|p| p.matches(&host_lc, port)) {
             return Ok(());
         }
-        return Err(anyhow!("host not in BROWSE_ALLOWED_HOSTS"));
+        return Err(anyhow!(
+            "host {host_lc} does not match any BROWSE_ALLOWED_HOSTS entry"
+        ));
     }
 
     if allow_private {
@@ -248,7 +552,7 @@ fn policy_allows_host(
 
     if host_is_obviously_local(&host_lc) {
         return Err(anyhow!(
-            "refusing to browse localhost (set BROWSE_ALLOW_PRIVATE=true or BROWSE_ALLOWED_HOSTS to override)"
+            "refusing to browse localhost (set BROWSE_ALLOW_PRIVATE=true, or allowlist it in BROWSE_ALLOWED_HOSTS and its addresses in BROWSE_ALLOW_CIDRS)"
         ));
     }
 
@@ -256,104 +560,309 @@ fn policy_allows_host(
 }
 
 async fn assert_browse_target_allowed(url: &Url, cfg: &BrowseConfig) -> Result<()> {
-    let allow_private = cfg.allow_private;
-    let allowed_hosts = cfg.allowed_hosts.clone();
-
     let Some(host) = url.host_str() else {
         return Err(anyhow!("url missing host"));
     };
 
-    policy_allows_host(host, allow_private, allowed_hosts.as_deref())?;
+    policy_allows_host(
+        host,
+        url.port_or_known_default(),
+        cfg.allow_private,
+        cfg.allowed_hosts.as_deref(),
+        &cfg.denied_hosts,
+    )?;
 
-    // If allowlist is set, we skip private-IP filtering to avoid surprising the user.
-    if allowed_hosts.is_some() {
+    let policy = cfg.ip_policy();
+    if policy.is_permissive() {
         return Ok(());
     }
+    let denied = |ip: IpAddr, net: IpNet| {
+        anyhow!(
+            "refusing to browse {host}: {ip} is in denied range {net} (set BROWSE_ALLOW_CIDRS or BROWSE_ALLOW_PRIVATE=true to override)"
+        )
+    };
 
     // If host is an IP literal, check it directly.
-    if let Ok(ip) = host.parse::<IpAddr>() {
-        if ip_is_private(ip) {
-            return Err(anyhow!(
-                "refusing to browse private/loopback IP (set BROWSE_ALLOW_PRIVATE=true or BROWSE_ALLOWED_HOSTS to override)"
-            ));
-        }
-        return Ok(());
+    let host_ip = host.trim_start_matches('[').trim_end_matches(']');
+    if let Ok(ip) = host_ip.parse::<IpAddr>() {
+        return match policy.denied_by(ip) {
+            Some(net) => Err(denied(ip, net)),
+            None => Ok(()),
+        };
     }
 
-    // Resolve DNS and block if any result is private.
-    let addrs = tokio::net::lookup_host((host, url.port_or_known_default().unwrap_or(80)))
+    // Resolve DNS and block if any result is denied. This gives a clear error
+    // up front; the pinned resolver enforces the same rule when connecting.
+    let addrs = cfg
+        .lookup
+        .lookup(host)
         .await
         .with_context(|| format!("failed to resolve host '{host}'"))?;
-
-    let mut saw_any = false;
-    for addr in addrs {
-        saw_any = true;
-        if ip_is_private(addr.ip()) {
-            return Err(anyhow!(
-                "refusing to browse host that resolves to private IP (set BROWSE_ALLOW_PRIVATE=true to override)"
-            ));
-        }
-    }
-
-    if !saw_any {
+    if addrs.is_empty() {
         return Err(anyhow!("host did not resolve"));
     }
+    for ip in addrs {
+        if let Some(net) = policy.denied_by(ip) {
+            return Err(denied(ip, net));
+        }
+    }
 
     Ok(())
 }
 
-fn decode_html_entities(text: &str) -> String {
-    let mut decoded = text
-        .replace("&nbsp;", " ")
-        .replace("&amp;", "&")
-        .replace("&lt;", "<")
-        .replace("&gt;", ">")
-        .replace("&quot;", "\"")
-        .replace("&#39;", "'");
-
-    let numeric_entity = Regex::new(r"&#(x?[0-9A-Fa-f]+);").expect("valid regex");
-    decoded = numeric_entity
-        .replace_all(&decoded, |caps: &regex::Captures<'_>| {
-            let raw = &caps[1];
-            let parsed = if let Some(hex) = raw.strip_prefix('x').or_else(|| raw.strip_prefix('X'))
-            {
-                u32::from_str_radix(hex, 16).ok()
-            } else {
-                raw.parse::<u32>().ok()
-            };
+/// Fetch robots.txt for `url`'s origin, following up to five redirects.
+///
+/// Returns the rules and whether they may be cached. Per RFC 9309 a missing
+/// robots.txt (4xx) allows everything and an unreachable one (5xx, network
+/// error) disallows everything; the latter is retried on the next call.
+async fn fetch_robots(
+    http: &reqwest::Client,
+    url: &Url,
+    agent: &str,
+    cfg: &BrowseConfig,
+) -> Result<(RobotsRules, bool)> {
+    let mut current = url.join("/robots.txt").context("invalid robots.txt url")?;
+    for _ in 0..=MAX_ROBOTS_REDIRECTS {
+        assert_browse_target_allowed(&current, cfg).await?;
+        let resp = match http.get(current.clone()).send().await {
+            Ok(resp) => resp,
+            Err(e) => {
+                tracing::warn!(url = %current, error = %e, "robots.txt unreachable; disallowing");
+                return Ok((RobotsRules::disallow_all(), false));
+            }
+        };
+        let status = resp.status();
+        if status.is_redirection() {
+            let next = resp
+                .headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|loc| current.join(loc).ok());
+            match next {
+                Some(next) => {
+                    current = next;
+                    continue;
+                }
+                None => return Ok((RobotsRules::default(), true)),
+            }
+        }
+        if status.is_client_error() {
+            return Ok((RobotsRules::default(), true));
+        }
+        if !status.is_success() {
+            tracing::warn!(url = %current, %status, "robots.txt unavailable; disallowing");
+            return Ok((RobotsRules::disallow_all(), false));
+        }
 
-            parsed
-                .and_then(char::from_u32)
-                .map(|ch| ch.to_string())
-                .unwrap_or_else(|| caps[0].to_string())
-        })
-        .into_owned();
+        let mut buf: Vec<u8> = Vec::new();
+        let mut stream = resp.bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            let Ok(chunk) = chunk else {
+                return Ok((RobotsRules::disallow_all(), false));
+            };
+            buf.extend_from_slice(&chunk);
+            if buf.len() >= MAX_ROBOTS_BYTES {
+                buf.truncate(MAX_ROBOTS_BYTES);
+                break;
+            }
+        }
+        let text = String::from_utf8_lossy(&buf);
+        return Ok((RobotsRules::parse(&text, agent), true));
+    }
+    // Too many redirects counts as unavailable, like a 4xx.
+    Ok((RobotsRules::default(), true))
+}
 
-    decoded
+/// Enforce robots.txt for `url` when `BROWSE_RESPECT_ROBOTS` is on.
+async fn assert_robots_allowed(
+    http: &reqwest::Client,
+    url: &Url,
+    cfg: &BrowseConfig,
+) -> Result<()> {
+    let Some(robots) = cfg.robots.as_deref() else {
+        return Ok(());
+    };
+    let origin = url.origin().ascii_serialization();
+    let rules = match robots.cached(&origin) {
+        Some(rules) => rules,
+        None => {
+            let (rules, cacheable) = fetch_robots(http, url, robots.agent(), cfg).await?;
+            let rules = Arc::new(rules);
+            if cacheable {
+                robots.insert(origin, rules.clone());
+            }
+            rules
+        }
+    };
+    robots.check(url, &rules, cfg.limits.queue_wait()).await
 }
 
 fn render_text(html: &str) -> String {
-    let breaks = Regex::new(r"(?is)</?(?:address|article|aside|blockquote|br|div|dl|fieldset|figcaption|figure|footer|form|h[1-6]|header|hr|li|main|nav|ol|p|pre|section|table|tbody|td|tfoot|th|thead|tr|ul)[^>]*>")
-        .expect("valid regex");
-    let without_tags = Regex::new(r"(?is)<[^>]+>").expect("valid regex");
-    let whitespace = Regex::new(r"[ \t\x0C\r]+").expect("valid regex");
-    let newline_runs = Regex::new(r"\n{3,}").expect("valid regex");
-
-    let with_breaks = breaks.replace_all(html, "\n");
-    let stripped = without_tags.replace_all(&with_breaks, " ");
-    let decoded = decode_html_entities(&stripped);
-    let normalized = whitespace.replace_all(&decoded, " ");
-    let normalized = normalized.replace("\n ", "\n").replace(" \n", "\n");
-    newline_runs
-        .replace_all(normalized.trim(), "\n\n")
-        .into_owned()
+    let doc = Html::parse_document(html);
+    let mut raw = String::new();
+    let mut skipping = None;
+    for edge in doc.tree.root().traverse() {
+        match edge {
+            Edge::Open(node) if skipping.is_none() => match node.value() {
+                Node::Text(text) => raw.push_str(text),
+                Node::Element(el) if is_non_content_element(el.name()) => {
+                    skipping = Some(node.id());
+                }
+                Node::Element(el) if is_block_element(el.name()) => raw.push('\n'),
+                _ => {}
+            },
+            Edge::Close(node) => {
+                if skipping == Some(node.id()) {
+                    skipping = None;
+                } else if skipping.is_none()
+                    && let Node::Element(el) = node.value()
+                    && is_block_element(el.name())
+                {
+                    raw.push('\n');
+                }
+            }
+            Edge::Open(_) => {}
+        }
+    }
+
+    // Collapse whitespace within lines and keep at most one blank line in a row.
+    let mut out = String::with_capacity(raw.len());
+    let mut blank_run = 0;
+    for line in raw.lines() {
+        let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+        if line.is_empty() {
+            blank_run += 1;
+            continue;
+        }
+        if !out.is_empty() {
+            out.push_str(if blank_run > 0 { "\n\n" } else { "\n" });
+        }
+        out.push_str(&line);
+        blank_run = 0;
+    }
+    out
 }
 
+/// Render readable content; [`BrowseFormat::Links`] is handled by `render_page`.
 fn render_html(html: &str, format: BrowseFormat) -> String {
     let cleaned = strip_styles_and_scripts(html);
     match format {
         BrowseFormat::Markdown => html2md::parse_html(&cleaned),
-        BrowseFormat::Text => render_text(&cleaned),
+        BrowseFormat::Text | BrowseFormat::Links => render_text(&cleaned),
+    }
+}
+
+pub fn parse_selector(selector: &str) -> Result<Selector> {
+    Selector::parse(selector).map_err(|e| anyhow!("invalid selector '{selector}': {e}"))
+}
+
+/// Outer HTML of the elements matching `selector`, in document order. Matches
+/// nested inside another match are already included and are not repeated.
+fn select_html(html: &str, selector: &str) -> Result<String> {
+    let sel = parse_selector(selector)?;
+    let doc = Html::parse_document(html);
+    let parts = doc
+        .select(&sel)
+        .filter(|el| {
+            !el.ancestors()
+                .filter_map(ElementRef::wrap)
+                .any(|ancestor| sel.matches(&ancestor))
+        })
+        .map(|el| el.html())
+        .collect();
+    join_selected(parts, selector)
+}
+
+/// Join selected elements for rendering. Each is wrapped in the bare parents it
+/// needs to survive being parsed on its own: the HTML parser drops a `<tr>` or
+/// `<td>` that is not inside a `<table>`, and with it the table structure.
+fn join_selected(parts: Vec<String>, selector: &str) -> Result<String> {
+    if parts.is_empty() {
+        return Err(anyhow!("no elements match selector '{selector}'"));
+    }
+    // Consecutive parts needing the same parents share them, so selected rows
+    // stay one table.
+    let mut groups: Vec<(&[&str], Vec<String>)> = Vec::new();
+    for part in parts {
+        let tag = part
+            .strip_prefix('<')
+            .and_then(|rest| {
+                rest.split(|c: char| c.is_whitespace() || c == '>' || c == '/')
+                    .next()
+            })
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let parents: &[&str] = match tag.as_str() {
+            "td" | "th" => &["table", "tr"],
+            "tr" | "thead" | "tbody" | "tfoot" | "caption" | "colgroup" => &["table"],
+            "li" => &["ul"],
+            "dt" | "dd" => &["dl"],
+            _ => &[],
+        };
+        match groups.last_mut() {
+            Some((last, members)) if !parents.is_empty() && *last == parents => members.push(part),
+            _ => groups.push((parents, vec![part])),
+        }
+    }
+    let wrapped: Vec<String> = groups
+        .into_iter()
+        .map(|(parents, members)| {
+            let open: String = parents.iter().map(|p| format!("<{p}>")).collect();
+            let close: String = parents.iter().rev().map(|p| format!("</{p}>")).collect();
+            format!("{open}{}{close}", members.join("\n"))
+        })
+        .collect();
+    Ok(wrapped.join("\n"))
+}
+
+/// Metadata always describes the whole page; `selector` and `extract` only
+/// narrow what is rendered.
+fn render_page(
+    html: &str,
+    page_url: &Url,
+    format: BrowseFormat,
+    extract: BrowseExtract,
+    selector: Option<&str>,
+) -> Result<BrowsePage> {
+    let selected = selector.map(|sel| select_html(html, sel)).transpose()?;
+    Ok(render_selected(
+        html,
+        selected.as_deref(),
+        page_url,
+        format,
+        extract,
+    ))
+}
+
+/// [`render_page`] for a selection that was already made, e.g. by Obscura.
+fn render_selected(
+    html: &str,
+    selected: Option<&str>,
+    page_url: &Url,
+    format: BrowseFormat,
+    extract: BrowseExtract,
+) -> BrowsePage {
+    let metadata = extract_metadata(html, page_url);
+    let html = selected.unwrap_or(html);
+    let main = match extract {
+        BrowseExtract::Full => None,
+        BrowseExtract::Article => crate::extract::main_content_html(html),
+    };
+    let html = main.as_deref().unwrap_or(html);
+    if format == BrowseFormat::Links {
+        let links = extract_links(html, page_url);
+        return BrowsePage {
+            content: render_links(&links),
+            metadata,
+            links,
+            from_disk_cache: false,
+        };
+    }
+    BrowsePage {
+        content: render_html(html, format),
+        metadata,
+        links: Vec::new(),
+        from_disk_cache: false,
     }
 }
 
@@ -364,23 +873,200 @@ fn enforce_max_bytes(output: String, max_bytes: usize, what: &str) -> Result<Str
     Ok(output)
 }
 
+/// A rendered page along with what could be learned about it.
+#[derive(Debug, Clone, Default)]
+pub struct BrowsePage {
+    pub content: String,
+    pub metadata: PageMetadata,
+    /// Populated for [`BrowseFormat::Links`] only.
+    pub links: Vec<PageLink>,
+    /// Whether the body came from the disk cache after a `304 Not Modified`.
+    pub from_disk_cache: bool,
+}
+
+/// One slice of a rendered document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrowseChunk {
+    pub content: String,
+    /// Character offset of `content` within the document.
+    pub offset: usize,
+    pub total_chars: usize,
+    /// Offset to request next, if the document continues.
+    pub next_offset: Option<usize>,
+}
+
+impl BrowseChunk {
+    /// Footer telling the caller where this chunk sits and how to continue.
+    /// The options set in `repeat` are spelled out, since the next browse call
+    /// must render the page the same way for the offset to line up.
+    pub fn continuation_hint(&self, repeat: &BrowseOptions) -> Option<String> {
+        let end = self.offset + self.content.chars().count();
+        match self.next_offset {
+            Some(next) => {
+                let mut args = Vec::new();
+                if let Some(format) = repeat.format {
+                    args.push(format!("format={}", format.as_str()));
+                }
+                if let Some(extract) = repeat.extract {
+                    args.push(format!("extract={}", extract.as_str()));
+                }
+                if let Some(selector) = &repeat.selector {
+                    args.push(format!("selector={selector:?}"));
+                }
+                args.push(format!("offset={next}"));
+                Some(format!(
+                    "[Characters {}-{end} of {}. Call browse again with {} to continue.]",
+                    self.offset,
+                    self.total_chars,
+                    args.join(", ")
+                ))
+            }
+            None if self.offset > 0 => Some(format!(
+                "[Characters {}-{end} of {}. End of document.]",
+                self.offset, self.total_chars
+            )),
+            None => None,
+        }
+    }
+}
+
+/// Byte index of the `n`th char of `s`, or `s.len()` if it is shorter.
+fn char_to_byte(s: &str, n: usize) -> usize {
+    s.char_indices().nth(n).map_or(s.len(), |(i, _)| i)
+}
+
+/// Where to end a chunk within `window`: before a heading if possible, then
+/// after a paragraph, a line or a word. Cuts in the first half are not worth it.
+fn chunk_break(window: &str) -> usize {
+    let min = window.len() / 2;
+    for (pattern, keep) in [("\n#", 1), ("\n\n", 2), ("\n", 1), (" ", 1)] {
+        if let Some(i) = window.rfind(pattern)
+            && i >= min
+        {
+            return i + keep;
+        }
+    }
+    window.len()
+}
+
+/// Take up to `max_chars` characters of `doc` starting at char `offset`.
+pub fn chunk_document(doc: &str, offset: usize, max_chars: usize) -> Result<BrowseChunk> {
+    let total_chars = doc.chars().count();
+    if offset > 0 && offset >= total_chars {
+        return Err(anyhow!(
+            "offset {offset} is past the end of the document ({total_chars} characters)"
+        ));
+    }
+    if max_chars == 0 {
+        return Err(anyhow!("max_chars must be greater than 0"));
+    }
+
+    let rest = &doc[char_to_byte(doc, offset)..];
+    let window_end = char_to_byte(rest, max_chars);
+    let end = if window_end == rest.len() {
+        window_end
+    } else {
+        chunk_break(&rest[..window_end])
+    };
+    let content = rest[..end].to_string();
+    let next = offset + content.chars().count();
+    Ok(BrowseChunk {
+        content,
+        offset,
+        total_chars,
+        next_offset: (next < total_chars).then_some(next),
+    })
+}
+
+/// Identifies a browse response in the in-memory cache.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BrowseCacheKey {
+    pub url: String,
+    pub format: BrowseFormat,
+    pub extract: BrowseExtract,
+    pub selector: Option<String>,
+    pub backend: BrowseBackend,
+}
+
+impl BrowseCacheKey {
+    pub fn new(url: &str, opts: &BrowseOptions, cfg: &BrowseConfig) -> Self {
+        Self {
+            url: url.trim().to_string(),
+            format: opts.format.unwrap_or(cfg.format),
+            extract: opts.extract.unwrap_or(cfg.extract),
+            selector: opts.selector.as_deref().map(str::trim).map(str::to_string),
+            backend: cfg.backend,
+        }
+    }
+}
+
 pub async fn browse_with_config(
     url: &str,
-    format: Option<BrowseFormat>,
+    opts: &BrowseOptions,
     cfg: &BrowseConfig,
-) -> Result<String> {
-    let format = format.unwrap_or(cfg.format);
+) -> Result<BrowsePage> {
+    let format = opts.format.unwrap_or(cfg.format);
+    let extract = opts.extract.unwrap_or(cfg.extract);
+    let selector = opts.selector.as_deref().map(str::trim);
+    if let Some(sel) = selector {
+        parse_selector(sel)?;
+    }
+    let permit = cfg.limits.acquire().await?;
     match cfg.backend {
-        BrowseBackend::Simple => browse_simple_with_config(url, format, cfg).await,
-        BrowseBackend::Obscura => browse_obscura_with_config(url, format, cfg).await,
+        BrowseBackend::Simple => {
+            let _permit = permit;
+            browse_simple_with_config(url, format, extract, selector, cfg).await
+        }
+        BrowseBackend::Obscura => {
+            browse_obscura_with_config(url, format, extract, selector, cfg, permit).await
+        }
     }
 }
 
+/// Run `fetch` over `urls`, at most `concurrency` at a time, and return the
+/// results in input order. Whatever has not finished once `deadline` has passed
+/// fails on its own instead of holding up the batch.
+///
+/// Each fetch still goes through the shared [`BrowseLimits`], so a batch
+/// cannot exceed the global concurrency or per-host rate.
+pub async fn browse_batch<'a, T, F, Fut>(
+    urls: &'a [String],
+    concurrency: usize,
+    deadline: Duration,
+    fetch: F,
+) -> Vec<Result<T>>
+where
+    F: Fn(&'a str) -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let until = tokio::time::Instant::now() + deadline;
+    // Futures are lazy, so building them all up front starts nothing; it keeps
+    // the mapping closure out of the stream so the batch stays `Send`.
+    let fetches: Vec<_> = urls
+        .iter()
+        .map(|url| tokio::time::timeout_at(until, fetch(url.trim())))
+        .collect();
+    futures_util::stream::iter(fetches)
+        .buffered(concurrency.max(1))
+        .map(|outcome| {
+            outcome.unwrap_or_else(|_| {
+                Err(anyhow!(
+                    "not finished within the {} s batch deadline (BROWSE_MANY_DEADLINE_SECS)",
+                    deadline.as_secs_f64()
+                ))
+            })
+        })
+        .collect()
+        .await
+}
+
 async fn browse_simple_with_config(
     url: &str,
     format: BrowseFormat,
+    extract: BrowseExtract,
+    selector: Option<&str>,
     cfg: &BrowseConfig,
-) -> Result<String> {
+) -> Result<BrowsePage> {
     let url = Url::parse(url).context("invalid url")?;
     match url.scheme() {
         "http" | "https" => {}
@@ -390,95 +1076,220 @@ async fn browse_simple_with_config(
     let follow_redirects = cfg.follow_redirects;
     let max_redirects = cfg.max_redirects;
     let max_bytes = cfg.max_bytes;
-    let timeout = cfg.timeout;
-
-    let http = reqwest::ClientBuilder::new()
-        .timeout(timeout)
-        .user_agent(cfg.user_agent.clone())
-        // Follow redirects manually so SSRF checks apply to each hop.
-        .redirect(reqwest::redirect::Policy::none())
-        .build()
-        .context("failed to build HTTP client")?;
+    let http = cfg.http_client()?;
 
     let mut current = url;
     for hop in 0..=max_redirects {
         assert_browse_target_allowed(&current, cfg).await?;
-
-        let resp = http
-            .get(current.clone())
-            .send()
-            .await
-            .context("request failed")?;
-
-        let status = resp.status();
-
-        if follow_redirects && status.is_redirection() {
-            let Some(loc) = resp.headers().get(reqwest::header::LOCATION) else {
-                return Err(anyhow!("redirect missing Location header"));
-            };
-            let loc = loc.to_str().context("invalid Location header")?;
-            current = current
-                .join(loc)
-                .with_context(|| format!("failed to resolve redirect location '{loc}'"))?;
-            if hop == max_redirects {
-                return Err(anyhow!(
-                    "too many redirects (BROWSE_MAX_REDIRECTS={max_redirects})"
-                ));
+        assert_robots_allowed(http, &current, cfg).await?;
+        cfg.limits
+            .wait_for_host(current.host_str().unwrap_or_default())
+            .await?;
+
+        let cached = match &cfg.disk_cache {
+            Some(disk) => load_cached(disk, &current).await,
+            None => None,
+        };
+        let mut req = http.get(current.clone());
+        if let Some(hit) = &cached {
+            if let Some(etag) = &hit.meta.etag {
+                req = req.header(reqwest::header::IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &hit.meta.last_modified {
+                req = req.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
             }
-            continue;
         }
+        let resp = req.send().await.context("request failed")?;
 
-        if !status.is_success() {
-            let body = resp.text().await.unwrap_or_default();
-            return Err(anyhow!("http error: {}: {}", status, body));
-        }
+        let status = resp.status();
 
-        // Gate on content-type to avoid trying to markdownify binaries.
-        if let Some(ct) = resp
-            .headers()
-            .get(reqwest::header::CONTENT_TYPE)
-            .and_then(|v| v.to_str().ok())
-        {
-            let ct_lc = ct.to_ascii_lowercase();
-            let ok = ct_lc.starts_with("text/")
-                || ct_lc.starts_with("application/xhtml+xml")
-                || ct_lc.starts_with("application/xml")
-                || ct_lc.starts_with("text/html");
-            if !ok {
-                return Err(anyhow!("unsupported content-type for browse: {ct}"));
+        let (buf, content_type, from_disk_cache) = match cached {
+            Some(hit) if status == reqwest::StatusCode::NOT_MODIFIED => {
+                tracing::debug!(url = %current, "browse body revalidated from disk cache");
+                (hit.body, hit.meta.content_type, true)
             }
-        }
+            _ => {
+                if follow_redirects && status.is_redirection() {
+                    let Some(loc) = resp.headers().get(reqwest::header::LOCATION) else {
+                        return Err(anyhow!("redirect missing Location header"));
+                    };
+                    let loc = loc.to_str().context("invalid Location header")?;
+                    current = current
+                        .join(loc)
+                        .with_context(|| format!("failed to resolve redirect location '{loc}'"))?;
+                    if hop == max_redirects {
+                        return Err(anyhow!(
+                            "too many redirects (BROWSE_MAX_REDIRECTS={max_redirects})"
+                        ));
+                    }
+                    continue;
+                }
+
+                if !status.is_success() {
+                    let body = resp.text().await.unwrap_or_default();
+                    return Err(anyhow!("http error: {}: {}", status, body));
+                }
+
+                let content_type = resp
+                    .headers()
+                    .get(reqwest::header::CONTENT_TYPE)
+                    .and_then(|v| v.to_str().ok())
+                    .map(str::to_string);
+
+                // Gate on content-type to avoid trying to markdownify binaries.
+                // Servers often label PDFs as generic binary, so those are let
+                // through here and sniffed once the body is in.
+                let mut sniff_pdf = false;
+                if let Some(ct) = content_type.as_deref() {
+                    let ct_lc = ct.to_ascii_lowercase();
+                    sniff_pdf = ct_lc.starts_with("application/octet-stream")
+                        || ct_lc.starts_with("binary/octet-stream");
+                    let ok = ct_lc.starts_with("text/")
+                        || ct_lc.starts_with("application/xhtml+xml")
+                        || ct_lc.starts_with("application/xml")
+                        || ct_lc.starts_with("text/html")
+                        || crate::pdf::is_pdf(Some(ct), b"")
+                        || sniff_pdf;
+                    if !ok {
+                        return Err(anyhow!("unsupported content-type for browse: {ct}"));
+                    }
+                }
+
+                let cache_meta = cfg.disk_cache.as_ref().and_then(|_| {
+                    cacheable_meta(&current, resp.headers(), content_type.as_deref())
+                });
+
+                let mut buf: Vec<u8> = Vec::new();
+                let mut stream = resp.bytes_stream();
+                while let Some(chunk) = stream.next().await {
+                    let chunk = chunk.context("read body failed")?;
+                    if buf.len() + chunk.len() > max_bytes {
+                        return Err(anyhow!("response exceeded BROWSE_MAX_BYTES ({max_bytes})"));
+                    }
+                    buf.extend_from_slice(&chunk);
+                }
+                if sniff_pdf && !crate::pdf::is_pdf(content_type.as_deref(), &buf) {
+                    return Err(anyhow!(
+                        "unsupported content-type for browse: {} (body is not a PDF)",
+                        content_type.as_deref().unwrap_or_default()
+                    ));
+                }
+
+                if let (Some(disk), Some(meta)) = (&cfg.disk_cache, cache_meta) {
+                    store_cached(disk, meta, buf.clone()).await;
+                }
+                (buf, content_type, false)
+            }
+        };
 
-        let mut buf: Vec<u8> = Vec::new();
-        let mut stream = resp.bytes_stream();
-        while let Some(chunk) = stream.next().await {
-            let chunk = chunk.context("read body failed")?;
-            if buf.len() + chunk.len() > max_bytes {
-                return Err(anyhow!("response exceeded BROWSE_MAX_BYTES ({max_bytes})"));
+        if crate::pdf::is_pdf(content_type.as_deref(), &buf) {
+            if format == BrowseFormat::Links {
+                return Err(anyhow!("links format is not supported for PDF documents"));
             }
-            buf.extend_from_slice(&chunk);
+            if selector.is_some() {
+                return Err(anyhow!("selector is not supported for PDF documents"));
+            }
+            let pdf = tokio::task::spawn_blocking(move || crate::pdf::extract(&buf, max_bytes))
+                .await
+                .context("PDF extraction task failed")??;
+            return Ok(BrowsePage {
+                content: crate::pdf::render(&pdf, format),
+                metadata: PageMetadata {
+                    title: pdf.title.clone(),
+                    author: pdf.author.clone(),
+                    page_count: Some(pdf.page_count),
+                    ..PageMetadata::default()
+                },
+                links: Vec::new(),
+                from_disk_cache,
+            });
         }
 
-        let html = String::from_utf8(buf).context("response was not valid utf-8")?;
-        let output = render_html(&html, format);
-        return enforce_max_bytes(output, max_bytes, "rendered output");
+        let decoded = crate::charset::decode_html(&buf, content_type.as_deref());
+        if decoded.lossy {
+            tracing::warn!(
+                url = %current,
+                encoding = decoded.encoding,
+                "browse body had invalid byte sequences; replaced them"
+            );
+        }
+        let mut page = render_page(&decoded.text, &current, format, extract, selector)?;
+        page.from_disk_cache = from_disk_cache;
+        return Ok(page);
     }
 
     Err(anyhow!("unreachable"))
 }
 
+/// What to store on disk for a response, or `None` if it cannot be revalidated
+/// later or asks not to be stored.
+fn cacheable_meta(
+    url: &Url,
+    headers: &reqwest::header::HeaderMap,
+    content_type: Option<&str>,
+) -> Option<CachedMeta> {
+    let header = |name| {
+        headers
+            .get(name)
+            .and_then(|v: &reqwest::header::HeaderValue| v.to_str().ok())
+            .map(str::to_string)
+    };
+    let no_store = header(reqwest::header::CACHE_CONTROL).is_some_and(|cc| {
+        cc.split(',')
+            .any(|directive| directive.trim().eq_ignore_ascii_case("no-store"))
+    });
+    let etag = header(reqwest::header::ETAG);
+    let last_modified = header(reqwest::header::LAST_MODIFIED);
+    if no_store || (etag.is_none() && last_modified.is_none()) {
+        return None;
+    }
+    Some(CachedMeta {
+        url: url.to_string(),
+        etag,
+        last_modified,
+        content_type: content_type.map(str::to_string),
+    })
+}
+
+async fn load_cached(disk: &DiskCache, url: &Url) -> Option<crate::disk_cache::CachedResponse> {
+    let disk = disk.clone();
+    let url = url.to_string();
+    tokio::task::spawn_blocking(move || disk.load(&url))
+        .await
+        .ok()
+        .flatten()
+}
+
+/// Failing to cache is not worth failing the request over.
+async fn store_cached(disk: &DiskCache, meta: CachedMeta, body: Vec<u8>) {
+    let disk = disk.clone();
+    let url = meta.url.clone();
+    let stored = tokio::task::spawn_blocking(move || disk.store(&meta, &body)).await;
+    if let Err(e) = stored.map_err(anyhow::Error::from).and_then(|r| r) {
+        tracing::warn!(url = %url, error = %e, "failed to write browse disk cache");
+    }
+}
+
 #[cfg(feature = "obscura-backend")]
-async fn new_obscura_page(cfg: &BrowseConfig) -> Result<obscura_browser::Page> {
+async fn new_obscura_page(
+    cfg: &BrowseConfig,
+) -> Result<(obscura_browser::Page, Option<crate::ssrf_proxy::SsrfProxy>)> {
+    // Obscura resolves names itself; route it through a proxy that only
+    // connects to vetted addresses. Keep the proxy alive as long as the page.
+    let proxy = match cfg.pinned_resolver() {
+        Some(resolver) => Some(crate::ssrf_proxy::SsrfProxy::start(resolver).await?),
+        None => None,
+    };
     let context = Arc::new(obscura_browser::BrowserContext::with_options(
         "browse".to_string(),
-        None,
+        proxy.as_ref().map(crate::ssrf_proxy::SsrfProxy::url),
         cfg.obscura_stealth,
     ));
     let page = obscura_browser::Page::new("browse-page".to_string(), context);
     if !cfg.obscura_stealth {
         page.http_client.set_user_agent(&cfg.user_agent).await;
     }
-    Ok(page)
+    Ok((page, proxy))
 }
 
 #[cfg(feature = "obscura-backend")]
@@ -500,6 +1311,15 @@ async fn navigate_obscura_page(
     };
     tokio::time::timeout(cfg.timeout, async {
         assert_browse_target_allowed(&parsed, cfg).await?;
+        if cfg.robots.is_some() {
+            // Not `cfg.http_client()`: pooled connections belong to the runtime
+            // that opened them, and each Obscura browse runs on its own.
+            let http = cfg.new_http_client()?;
+            assert_robots_allowed(&http, &parsed, cfg).await?;
+        }
+        cfg.limits
+            .wait_for_host(parsed.host_str().unwrap_or_default())
+            .await?;
         page.navigate_with_wait(url, wait_until)
             .await
             .map_err(|e| anyhow!("obscura navigation failed: {e}"))
@@ -508,45 +1328,104 @@ async fn navigate_obscura_page(
     .map_err(|_| anyhow!("obscura navigation timed out after {:?}", cfg.timeout))?
 }
 
+/// Where the page ended up after redirects, as the document reports it.
+#[cfg(feature = "obscura-backend")]
+fn obscura_page_url(page: &mut obscura_browser::Page) -> Option<Url> {
+    let href = page.evaluate("document.location.href").to_string();
+    // Evaluation results are rendered as JSON, so a string comes back quoted.
+    let href = serde_json::from_str::<String>(&href).unwrap_or(href);
+    Url::parse(&href)
+        .ok()
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+}
+
 #[cfg(feature = "obscura-backend")]
 async fn browse_obscura_with_config(
     url: &str,
     format: BrowseFormat,
+    extract: BrowseExtract,
+    selector: Option<&str>,
     cfg: &BrowseConfig,
-) -> Result<String> {
+    permit: tokio::sync::OwnedSemaphorePermit,
+) -> Result<BrowsePage> {
+    let base = Url::parse(url).context("invalid url")?;
     let url = url.to_string();
+    let selector = selector.map(str::to_string);
     let cfg = cfg.clone();
+    // The thread keeps running if the caller gives up on it (e.g. a batch
+    // deadline), so it holds the browse slot rather than the caller.
     tokio::task::spawn_blocking(move || {
+        let _permit = permit;
         let rt = tokio::runtime::Builder::new_current_thread()
             .enable_all()
             .build()
             .context("failed to build Obscura runtime")?;
         rt.block_on(async move {
-            let mut page = new_obscura_page(&cfg).await?;
+            let (mut page, _proxy) = new_obscura_page(&cfg).await?;
             navigate_obscura_page(&mut page, &url, &cfg).await?;
-            let output = tokio::time::timeout(cfg.timeout, async {
-                page.with_dom(|dom| match format {
-                    BrowseFormat::Markdown => {
+            // Relative links and canonical URLs resolve against the final URL.
+            let base = obscura_page_url(&mut page).unwrap_or(base);
+            tokio::time::timeout(cfg.timeout, async {
+                let (html, text, matches) = page
+                    .with_dom(|dom| {
                         let html = if let Ok(Some(html_node)) = dom.query_selector("html") {
                             dom.outer_html(html_node)
                         } else {
                             dom.inner_html(dom.document())
                         };
-                        render_html(&html, BrowseFormat::Markdown)
-                    }
-                    BrowseFormat::Text => {
-                        if let Ok(Some(body)) = dom.query_selector("body") {
-                            dom.text_content(body)
-                        } else {
-                            String::new()
-                        }
-                    }
-                })
-                .unwrap_or_default()
+                        // Plain text of the whole body needs no re-parsing.
+                        let text = match (format, extract, &selector) {
+                            (BrowseFormat::Text, BrowseExtract::Full, None) => {
+                                Some(if let Ok(Some(body)) = dom.query_selector("body") {
+                                    dom.text_content(body)
+                                } else {
+                                    String::new()
+                                })
+                            }
+                            _ => None,
+                        };
+                        // Select in Obscura's own DOM, the tree scripts built;
+                        // matches inside another match are left out, as in
+                        // `select_html`.
+                        let matches = selector.as_deref().map(|sel| {
+                            let nested = dom
+                                .query_selector_all(&format!(":is({sel}) :is({sel})"))
+                                .unwrap_or_default();
+                            dom.query_selector_all(sel)
+                                .unwrap_or_default()
+                                .into_iter()
+                                .filter(|node| !nested.contains(node))
+                                .map(|node| dom.outer_html(node))
+                                .collect::<Vec<_>>()
+                        });
+                        (html, text, matches)
+                    })
+                    .unwrap_or_default();
+                // The simple backend caps the response body; Obscura fetches
+                // for itself, so cap the document it built instead.
+                let html = enforce_max_bytes(html, cfg.max_bytes, "rendered document")?;
+                let selected = selector
+                    .as_deref()
+                    .map(|sel| join_selected(matches.unwrap_or_default(), sel))
+                    .transpose()?;
+                match text {
+                    Some(content) => Ok(BrowsePage {
+                        content,
+                        metadata: extract_metadata(&html, &base),
+                        links: Vec::new(),
+                        from_disk_cache: false,
+                    }),
+                    None => Ok(render_selected(
+                        &html,
+                        selected.as_deref(),
+                        &base,
+                        format,
+                        extract,
+                    )),
+                }
             })
             .await
-            .map_err(|_| anyhow!("obscura render timed out after {:?}", cfg.timeout))?;
-            enforce_max_bytes(output, cfg.max_bytes, "rendered output")
+            .map_err(|_| anyhow!("obscura render timed out after {:?}", cfg.timeout))?
         })
     })
     .await
@@ -557,8 +1436,11 @@ async fn browse_obscura_with_config(
 async fn browse_obscura_with_config(
     _url: &str,
     _format: BrowseFormat,
+    _extract: BrowseExtract,
+    _selector: Option<&str>,
     _cfg: &BrowseConfig,
-) -> Result<String> {
+    _permit: tokio::sync::OwnedSemaphorePermit,
+) -> Result<BrowsePage> {
     Err(anyhow!(
         "BROWSE_BACKEND=obscura requires building with --features obscura-backend"
     ))
@@ -570,16 +1452,18 @@ pub async fn browse_eval_with_config(
     script: &str,
     cfg: &BrowseConfig,
 ) -> Result<String> {
+    let permit = cfg.limits.acquire().await?;
     let url = url.to_string();
     let script = script.to_string();
     let cfg = cfg.clone();
     tokio::task::spawn_blocking(move || {
+        let _permit = permit;
         let rt = tokio::runtime::Builder::new_current_thread()
             .enable_all()
             .build()
             .context("failed to build Obscura runtime")?;
         rt.block_on(async move {
-            let mut page = new_obscura_page(&cfg).await?;
+            let (mut page, _proxy) = new_obscura_page(&cfg).await?;
             navigate_obscura_page(&mut page, &url, &cfg).await?;
             let output =
                 tokio::time::timeout(cfg.timeout, async { page.evaluate(&script).to_string() })
@@ -606,7 +1490,47 @@ pub async fn browse_eval_with_config(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    /// Answer each request on loopback with `respond(request head)`, one
+    /// connection per request. Returns the base URL and the heads received.
+    async fn serve<F>(respond: F) -> (String, Arc<std::sync::Mutex<Vec<String>>>)
+    where
+        F: Fn(&str) -> Vec<u8> + Send + Sync + 'static,
+    {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((mut conn, _)) = listener.accept().await {
+                let mut head = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !head.ends_with(b"\r\n\r\n") {
+                    match conn.read(&mut buf).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => head.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let head = String::from_utf8_lossy(&head).into_owned();
+                let response = respond(&head);
+                seen.lock().unwrap().push(head);
+                let _ = conn.write_all(&response).await;
+            }
+        });
+        (base, requests)
+    }
+
+    fn http_response(headers: &str, body: &[u8]) -> Vec<u8> {
+        let mut out = format!(
+            "HTTP/1.1 200 OK\r\n{headers}Content-Length: {}\r\nConnection: close\r\n\r\n",
+            body.len()
+        )
+        .into_bytes();
+        out.extend_from_slice(body);
+        out
+    }
 
     #[test]
     fn strips_style_and_script_tags() {
@@ -617,35 +1541,666 @@ mod tests {
         assert!(cleaned.contains("<h1>Hi</h1>"));
     }
 
+    struct Fixture {
+        name: &'static str,
+        html: &'static str,
+        expect: &'static [&'static str],
+        reject: &'static [&'static str],
+    }
+
+    const FIXTURES: &[Fixture] = &[
+        Fixture {
+            name: "script_in_attribute",
+            html: include_str!("../tests/fixtures/html/script_in_attribute.html"),
+            expect: &["Linked words", "After the attributes."],
+            reject: &["not a tag", "x = "],
+        },
+        Fixture {
+            name: "comments_and_cdata",
+            html: include_str!("../tests/fixtures/html/comments_and_cdata.html"),
+            expect: &["Visible paragraph.", "Second paragraph.", "Last paragraph."],
+            reject: &["hidden comment", "bogus cdata", "svg cdata"],
+        },
+        Fixture {
+            name: "noscript_template",
+            html: include_str!("../tests/fixtures/html/noscript_template.html"),
+            expect: &["Real content."],
+            reject: &["enable JavaScript", "Template-only"],
+        },
+        Fixture {
+            name: "svg",
+            html: include_str!("../tests/fixtures/html/svg.html"),
+            expect: &["Chart", "Caption below the chart."],
+            reject: &["svg title", "svg label", "fill:red"],
+        },
+        Fixture {
+            name: "entities",
+            html: include_str!("../tests/fixtures/html/entities.html"),
+            expect: &[
+                "Rust \u{2014} fast\u{2026} caf\u{e9} \u{a9}",
+                "\u{1F980}",
+                "<b>",
+            ],
+            reject: &["&mdash;", "&hellip;", "&eacute;", "&#x1F980;"],
+        },
+        Fixture {
+            name: "tricky_scripts",
+            html: include_str!("../tests/fixtures/html/tricky_scripts.html"),
+            expect: &[
+                "Boldtext and italic words.",
+                "One",
+                "Two",
+                "Cell A",
+                "Cell B",
+            ],
+            reject: &["injected", "still script", "json-ld", "color: red"],
+        },
+    ];
+
+    #[test]
+    fn renders_text_fixtures() {
+        for fixture in FIXTURES {
+            let text = render_html(fixture.html, BrowseFormat::Text);
+            for needle in fixture.expect {
+                assert!(
+                    text.contains(needle),
+                    "{}: missing {needle:?} in {text:?}",
+                    fixture.name
+                );
+            }
+            for needle in fixture.reject {
+                assert!(
+                    !text.contains(needle),
+                    "{}: unexpected {needle:?} in {text:?}",
+                    fixture.name
+                );
+            }
+        }
+    }
+
     #[test]
-    fn ip_private_v4_cases() {
-        assert!(ip_is_private(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1))));
-        assert!(ip_is_private(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))));
-        assert!(ip_is_private(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1))));
-        assert!(!ip_is_private(IpAddr::V4(Ipv4Addr::new(8, 8, 8, 8))));
+    fn cleans_markdown_fixtures() {
+        for fixture in FIXTURES {
+            let cleaned = Html::parse_document(&strip_styles_and_scripts(fixture.html));
+            let leftover = cleaned.tree.nodes().find(|node| match node.value() {
+                Node::Comment(_) => true,
+                Node::Element(el) => is_non_content_element(el.name()),
+                _ => false,
+            });
+            assert!(
+                leftover.is_none(),
+                "{}: {:?} survived",
+                fixture.name,
+                leftover.map(|node| node.value().clone())
+            );
+            let md = render_html(fixture.html, BrowseFormat::Markdown);
+            for needle in fixture.reject {
+                assert!(
+                    !md.contains(needle),
+                    "{}: unexpected {needle:?} in {md:?}",
+                    fixture.name
+                );
+            }
+        }
     }
 
     #[test]
-    fn ip_private_v6_cases() {
-        assert!(ip_is_private(IpAddr::V6(Ipv6Addr::LOCALHOST)));
-        assert!(ip_is_private(IpAddr::V6(Ipv6Addr::UNSPECIFIED)));
-        assert!(ip_is_private(IpAddr::V6("fc00::1".parse().unwrap())));
-        assert!(ip_is_private(IpAddr::V6("fe80::1".parse().unwrap())));
-        assert!(!ip_is_private(IpAddr::V6(
-            "2606:4700:4700::1111".parse().unwrap()
-        )));
+    fn text_keeps_block_structure() {
+        let text = render_text("<h1>Title</h1><p>First   line</p><p>Second<br>line</p>");
+        assert_eq!(text, "Title\n\nFirst line\n\nSecond\n\nline");
+    }
+
+    #[test]
+    fn selector_narrows_rendering_but_not_metadata() {
+        let html = r#"<html><head><title>API</title></head><body>
+            <nav>Menu</nav>
+            <section class="ref"><h2>fn open</h2><div class="ref"><p>Opens a file.</p></div></section>
+            <p>Footer text</p>
+            <section class="ref"><h2>fn close</h2></section>
+        </body></html>"#;
+        let url = Url::parse("https://example.com/api").unwrap();
+        let page = render_page(
+            html,
+            &url,
+            BrowseFormat::Text,
+            BrowseExtract::Full,
+            Some(".ref"),
+        )
+        .unwrap();
+        assert_eq!(page.content, "fn open\n\nOpens a file.\n\nfn close");
+        assert_eq!(page.metadata.title.as_deref(), Some("API"));
+
+        let missing = render_page(
+            html,
+            &url,
+            BrowseFormat::Text,
+            BrowseExtract::Full,
+            Some("table"),
+        );
+        assert!(
+            missing
+                .unwrap_err()
+                .to_string()
+                .contains("no elements match")
+        );
+        assert!(parse_selector("div[").is_err());
+    }
+
+    #[test]
+    fn selected_rows_and_items_keep_their_structure() {
+        let html = r#"<html><body>
+            <table><tbody>
+                <tr class="hit"><td>open</td><td>Opens a file.</td></tr>
+                <tr><td>skip</td><td>Not selected.</td></tr>
+                <tr class="hit"><td>close</td><td>Closes it.</td></tr>
+            </tbody></table>
+            <ol><li class="hit">First step</li><li>Second step</li></ol>
+        </body></html>"#;
+        let url = Url::parse("https://example.com/api").unwrap();
+        let render = |format| {
+            render_page(html, &url, format, BrowseExtract::Full, Some(".hit"))
+                .unwrap()
+                .content
+        };
+
+        let md = render(BrowseFormat::Markdown);
+        // One table: the two selected rows and the separator after the first.
+        let rows: Vec<&str> = md.lines().filter(|l| l.starts_with('|')).collect();
+        assert_eq!(rows.len(), 3, "{md}");
+        assert!(rows[0].contains("Opens a file."), "{md}");
+        assert!(rows[2].contains("Closes it."), "{md}");
+        assert!(!md.contains("skip"), "{md}");
+        assert!(md.contains("First step"), "{md}");
+        assert!(!md.contains("Second step"), "{md}");
+
+        let text = render(BrowseFormat::Text);
+        assert!(!text.contains("openOpens"), "{text}");
+
+        let cell = render_page(
+            html,
+            &url,
+            BrowseFormat::Text,
+            BrowseExtract::Full,
+            Some("td"),
+        );
+        assert!(cell.unwrap().content.contains("close"));
+    }
+
+    #[test]
+    fn chunks_on_headings_and_paragraphs() {
+        let doc = "# Intro\n\nFirst paragraph here.\n\n## Usage\n\nSecond paragraph.\n";
+        let first = chunk_document(doc, 0, 40).unwrap();
+        assert_eq!(first.content, "# Intro\n\nFirst paragraph here.\n\n");
+        assert_eq!(first.total_chars, doc.len());
+        let next = first.next_offset.unwrap();
+        let same = BrowseOptions::default();
+        assert_eq!(
+            first.continuation_hint(&same).unwrap(),
+            "[Characters 0-32 of 60. Call browse again with offset=32 to continue.]"
+        );
+        let research = BrowseOptions {
+            format: Some(BrowseFormat::Markdown),
+            extract: Some(BrowseExtract::Article),
+            selector: None,
+        };
+        assert!(
+            first
+                .continuation_hint(&research)
+                .unwrap()
+                .contains("with format=markdown, extract=article, offset=32 to")
+        );
+
+        let second = chunk_document(doc, next, 40).unwrap();
+        assert_eq!(second.content, "## Usage\n\nSecond paragraph.\n");
+        assert_eq!(second.next_offset, None);
+        assert!(
+            second
+                .continuation_hint(&same)
+                .unwrap()
+                .contains("End of document")
+        );
+
+        let whole = chunk_document(doc, 0, 1_000).unwrap();
+        assert_eq!(whole.content, doc);
+        assert_eq!(whole.continuation_hint(&same), None);
+
+        assert!(chunk_document(doc, doc.len(), 40).is_err());
+    }
+
+    #[test]
+    fn chunks_count_characters_not_bytes() {
+        let doc = "\u{e9}".repeat(10);
+        let chunk = chunk_document(&doc, 3, 4).unwrap();
+        assert_eq!(chunk.content, "\u{e9}".repeat(4));
+        assert_eq!(chunk.next_offset, Some(7));
+    }
+
+    #[test]
+    fn ip_policy_follows_ssrf_settings() {
+        let ip = |s: &str| s.parse::<IpAddr>().unwrap();
+        let cfg = BrowseConfig::default();
+        let policy = cfg.ip_policy();
+        assert!(policy.denied_by(ip("127.0.0.1")).is_some());
+        assert!(policy.denied_by(ip("10.0.0.1")).is_some());
+        assert!(policy.denied_by(ip("::1")).is_some());
+        assert!(policy.denied_by(ip("8.8.8.8")).is_none());
+
+        let cfg = BrowseConfig {
+            allow_private: true,
+            deny_cidrs: vec!["10.0.0.0/8".parse().unwrap()],
+            ..BrowseConfig::default()
+        };
+        let policy = cfg.ip_policy();
+        assert!(policy.denied_by(ip("127.0.0.1")).is_none());
+        assert!(policy.denied_by(ip("10.0.0.1")).is_some());
+        assert!(cfg.pinned_resolver().is_some());
+
+        // An allowlist narrows the hosts but keeps the built-in deny set.
+        let cfg = BrowseConfig {
+            allowed_hosts: Some(vec![HostPattern::parse("localhost").unwrap()]),
+            ..BrowseConfig::default()
+        };
+        assert!(cfg.ip_policy().denied_by(ip("127.0.0.1")).is_some());
+        assert!(cfg.pinned_resolver().is_some());
+    }
+
+    #[tokio::test]
+    async fn allowlisted_hosts_still_get_the_ip_deny_set() {
+        let url = Url::parse("http://api.example.com/").unwrap();
+        let lookup =
+            || crate::resolver::tests::FakeLookup::with(&[("api.example.com", &[&["127.0.0.1"]])]);
+        let allowed =
+            || Some(parse_host_patterns("BROWSE_ALLOWED_HOSTS", "*.example.com").unwrap());
+
+        let cfg = BrowseConfig {
+            lookup: lookup(),
+            allowed_hosts: allowed(),
+            ..BrowseConfig::default()
+        };
+        let err = assert_browse_target_allowed(&url, &cfg).await.unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("127.0.0.1 is in denied range 127.0.0.0/8"),
+            "{err}"
+        );
+
+        // Explicitly permitting the range lets the allowlisted host through.
+        let cfg = BrowseConfig {
+            lookup: lookup(),
+            allowed_hosts: allowed(),
+            allow_cidrs: vec!["127.0.0.1/32".parse().unwrap()],
+            ..BrowseConfig::default()
+        };
+        assert_browse_target_allowed(&url, &cfg).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn robots_fetch_is_pinned_against_dns_rebinding() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use tokio::io::AsyncWriteExt;
+
+        // A permissive robots.txt on loopback that counts its visitors.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let hits = Arc::new(AtomicUsize::new(0));
+        let server = tokio::spawn({
+            let hits = hits.clone();
+            async move {
+                while let Ok((mut conn, _)) = listener.accept().await {
+                    hits.fetch_add(1, Ordering::SeqCst);
+                    let body = "User-agent: *\nDisallow:\n";
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                        body.len()
+                    );
+                    let _ = conn.write_all(response.as_bytes()).await;
+                }
+            }
+        });
+        let url = Url::parse(&format!("http://rebind.test:{port}/page")).unwrap();
+        let robots = || Some(Arc::new(Robots::new("searxng-mcp/test")));
+
+        // Public for the SSRF check, loopback when the robots client connects:
+        // the connection is refused, and an unreachable robots.txt disallows.
+        let cfg = BrowseConfig {
+            lookup: crate::resolver::tests::FakeLookup::with(&[(
+                "rebind.test",
+                &[&["93.184.216.34"], &["127.0.0.1"]],
+            )]),
+            robots: robots(),
+            ..BrowseConfig::default()
+        };
+        let http = cfg.new_http_client().unwrap();
+        let err = assert_robots_allowed(&http, &url, &cfg).await.unwrap_err();
+        assert!(
+            err.downcast_ref::<crate::robots::RobotsDisallowed>()
+                .is_some(),
+            "{err:#}"
+        );
+        assert_eq!(hits.load(Ordering::SeqCst), 0);
+
+        // Control: once loopback is allowed, the same client reaches the server.
+        let cfg = BrowseConfig {
+            lookup: crate::resolver::tests::FakeLookup::with(&[("rebind.test", &[&["127.0.0.1"]])]),
+            robots: robots(),
+            allow_cidrs: vec!["127.0.0.1/32".parse().unwrap()],
+            ..BrowseConfig::default()
+        };
+        let http = cfg.new_http_client().unwrap();
+        assert_robots_allowed(&http, &url, &cfg).await.unwrap();
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn denies_literal_and_resolved_addresses_by_rule() {
+        let lookup = crate::resolver::tests::FakeLookup::with(&[(
+            "metadata.test",
+            &[&["93.184.216.34", "169.254.169.254"]],
+        )]);
+        let cfg = BrowseConfig {
+            lookup,
+            ..BrowseConfig::default()
+        };
+        let check = |url: &str| {
+            let url = Url::parse(url).unwrap();
+            let cfg = cfg.clone();
+            async move {
+                assert_browse_target_allowed(&url, &cfg)
+                    .await
+                    .unwrap_err()
+                    .to_string()
+            }
+        };
+        assert!(
+            check("http://[::ffff:127.0.0.1]/")
+                .await
+                .contains("127.0.0.1 is in denied range 127.0.0.0/8")
+        );
+        assert!(check("http://198.18.0.1/").await.contains("198.18.0.0/15"));
+        assert!(
+            check("http://metadata.test/")
+                .await
+                .contains("169.254.169.254 is in denied range 169.254.0.0/16")
+        );
+    }
+
+    #[tokio::test]
+    async fn pinned_resolver_blocks_dns_rebinding() {
+        // Public for the up-front check, loopback when the client connects.
+        let lookup = crate::resolver::tests::FakeLookup::with(&[(
+            "rebind.test",
+            &[&["93.184.216.34"], &["127.0.0.1"]],
+        )]);
+        let cfg = BrowseConfig {
+            lookup,
+            ..BrowseConfig::default()
+        };
+        let err = browse_with_config("http://rebind.test/", &BrowseOptions::default(), &cfg)
+            .await
+            .unwrap_err();
+        assert!(
+            format!("{err:#}").contains("resolves only to denied addresses"),
+            "{err:#}"
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn batch_keeps_input_order_and_enforces_deadline() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let urls: Vec<String> = ["80", "10", "5000", "30", "bad"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let running = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let results = browse_batch(&urls, 2, Duration::from_millis(300), |url| {
+            let (running, peak) = (&running, &peak);
+            async move {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                let ms: Result<u64> = url.parse().map_err(|_| anyhow!("unparsable {url}"));
+                let outcome = match ms {
+                    Ok(ms) => {
+                        tokio::time::sleep(Duration::from_millis(ms)).await;
+                        Ok(ms)
+                    }
+                    Err(e) => Err(e),
+                };
+                running.fetch_sub(1, Ordering::SeqCst);
+                outcome
+            }
+        })
+        .await;
+
+        assert_eq!(results.len(), 5);
+        assert_eq!(results[0].as_ref().unwrap(), &80);
+        assert_eq!(results[1].as_ref().unwrap(), &10);
+        let timed_out = results[2].as_ref().unwrap_err().to_string();
+        assert!(timed_out.contains("deadline"), "{timed_out}");
+        assert_eq!(results[3].as_ref().unwrap(), &30);
+        assert_eq!(
+            results[4].as_ref().unwrap_err().to_string(),
+            "unparsable bad"
+        );
+        assert!(peak.load(Ordering::SeqCst) <= 2);
     }
 
     #[test]
-    fn policy_allowlist_overrides_private_block() {
+    fn policy_allowlist_overrides_localhost_name_block() {
         let host = "localhost";
-        let allowed = vec!["localhost".to_string()];
+        let allowed = vec![HostPattern::parse("localhost").unwrap()];
 
-        // Allowlist includes localhost: should pass even if allow_private=false.
-        assert!(policy_allows_host(host, false, Some(&allowed)).is_ok());
+        // Allowlist includes localhost: the name check passes even if
+        // allow_private=false (the IP policy still applies to its addresses).
+        assert!(policy_allows_host(host, Some(80), false, Some(&allowed), &[]).is_ok());
 
         // Allowlist excludes localhost: should fail.
-        let allowed_other = vec!["example.com".to_string()];
-        assert!(policy_allows_host(host, false, Some(&allowed_other)).is_err());
+        let allowed_other = vec![HostPattern::parse("example.com").unwrap()];
+        assert!(policy_allows_host(host, Some(80), false, Some(&allowed_other), &[]).is_err());
+    }
+
+    #[test]
+    fn denied_hosts_apply_with_or_without_allowlist() {
+        let denied =
+            parse_host_patterns("BROWSE_DENIED_HOSTS", "*.ads.example, tracker.test:443").unwrap();
+        let allowed = parse_host_patterns("BROWSE_ALLOWED_HOSTS", ".example").unwrap();
+
+        let err = policy_allows_host("x.ads.example", Some(443), false, None, &denied).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "refusing to browse x.ads.example: it matches BROWSE_DENIED_HOSTS entry '*.ads.example'"
+        );
+        let err = policy_allows_host("x.ads.example", Some(443), false, Some(&allowed), &denied)
+            .unwrap_err();
+        assert!(err.to_string().contains("'*.ads.example'"), "{err}");
+        assert!(policy_allows_host("tracker.test", Some(443), false, None, &denied).is_err());
+        assert!(policy_allows_host("tracker.test", Some(80), false, None, &denied).is_ok());
+        assert!(
+            policy_allows_host("docs.example", Some(443), false, Some(&allowed), &denied).is_ok()
+        );
+        assert!(
+            policy_allows_host("example.org", Some(443), false, Some(&allowed), &denied).is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn sniffs_generic_binary_responses_for_pdf() {
+        let pdf = crate::pdf::tests::sample_pdf(&["Hello from a PDF"]);
+        let (base, _) = serve(move |head| {
+            let binary = "Content-Type: application/octet-stream\r\n";
+            if head.starts_with("GET /doc ") {
+                http_response(binary, &pdf)
+            } else if head.starts_with("GET /blob ") {
+                http_response(binary, b"\x00\x01\x02 not a document")
+            } else {
+                http_response("Content-Type: image/png\r\n", b"\x89PNG")
+            }
+        })
+        .await;
+        let cfg = BrowseConfig {
+            allow_private: true,
+            ..BrowseConfig::default()
+        };
+        let browse = |path: &str| {
+            let url = format!("{base}{path}");
+            let cfg = cfg.clone();
+            async move { browse_with_config(&url, &BrowseOptions::default(), &cfg).await }
+        };
+
+        let page = browse("/doc").await.unwrap();
+        assert!(
+            page.content.contains("Hello from a PDF"),
+            "{}",
+            page.content
+        );
+        assert_eq!(page.metadata.page_count, Some(1));
+
+        let err = browse("/blob").await.unwrap_err().to_string();
+        assert!(err.contains("body is not a PDF"), "{err}");
+        let err = browse("/image.png").await.unwrap_err().to_string();
+        assert!(
+            err.contains("unsupported content-type for browse: image/png"),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn revalidates_disk_cached_pages() {
+        let (base, requests) = serve(|head| {
+            let head = head.to_ascii_lowercase();
+            if head.starts_with("get /private ") {
+                http_response(
+                    "Content-Type: text/html\r\nCache-Control: private, no-store\r\nETag: \"p1\"\r\n",
+                    b"<p>Private body</p>",
+                )
+            } else if head.contains("if-none-match: \"v1\"") {
+                b"HTTP/1.1 304 Not Modified\r\nETag: \"v1\"\r\nConnection: close\r\n\r\n".to_vec()
+            } else {
+                http_response(
+                    "Content-Type: text/html\r\nETag: \"v1\"\r\nLast-Modified: Wed, 01 May 2024 00:00:00 GMT\r\n",
+                    b"<p>Cached body</p>",
+                )
+            }
+        })
+        .await;
+        let cfg = BrowseConfig {
+            allow_private: true,
+            disk_cache: Some(crate::disk_cache::tests::temp_cache("revalidate", 1 << 20)),
+            ..BrowseConfig::default()
+        };
+        let browse = |path: &str| {
+            let url = format!("{base}{path}");
+            let cfg = cfg.clone();
+            async move {
+                browse_with_config(&url, &BrowseOptions::default(), &cfg)
+                    .await
+                    .unwrap()
+            }
+        };
+        let last_request = || {
+            requests
+                .lock()
+                .unwrap()
+                .last()
+                .unwrap()
+                .to_ascii_lowercase()
+        };
+
+        let first = browse("/page").await;
+        assert!(!first.from_disk_cache);
+        assert!(!last_request().contains("if-none-match"));
+
+        // The stored validators go out, and a 304 serves the stored body.
+        let second = browse("/page").await;
+        let sent = last_request();
+        assert!(sent.contains("if-none-match: \"v1\""), "{sent}");
+        assert!(
+            sent.contains("if-modified-since: wed, 01 may 2024 00:00:00 gmt"),
+            "{sent}"
+        );
+        assert!(second.from_disk_cache);
+        assert_eq!(second.content, first.content);
+        assert!(second.content.contains("Cached body"));
+
+        // no-store responses are never kept, so there is nothing to revalidate.
+        for _ in 0..2 {
+            let page = browse("/private").await;
+            assert!(!page.from_disk_cache);
+            assert!(page.content.contains("Private body"));
+            assert!(!last_request().contains("if-none-match"));
+        }
+        assert_eq!(requests.lock().unwrap().len(), 4);
+    }
+
+    /// Per-call latency of browsing one page with a client per call vs the
+    /// shared one. Run with
+    /// `cargo test --release pooled_client_latency -- --ignored --nocapture`.
+    #[tokio::test]
+    #[ignore = "benchmark"]
+    async fn pooled_client_latency() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Unlike `serve`, keep connections alive so there is something to reuse,
+        // and reply without Nagle delays.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/page", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut conn, _)) = listener.accept().await {
+                conn.set_nodelay(true).unwrap();
+                tokio::spawn(async move {
+                    let response = http_response("Content-Type: text/html\r\n", b"<p>Hello</p>");
+                    let response = String::from_utf8(response)
+                        .unwrap()
+                        .replace("Connection: close", "Connection: keep-alive");
+                    let mut head = Vec::new();
+                    let mut buf = [0u8; 1024];
+                    loop {
+                        match conn.read(&mut buf).await {
+                            Ok(0) | Err(_) => break,
+                            Ok(n) => head.extend_from_slice(&buf[..n]),
+                        }
+                        while let Some(end) = head.windows(4).position(|w| w == b"\r\n\r\n") {
+                            head.drain(..end + 4);
+                            if conn.write_all(response.as_bytes()).await.is_err() {
+                                return;
+                            }
+                        }
+                    }
+                });
+            }
+        });
+
+        let config = || BrowseConfig {
+            allow_private: true,
+            limits: Arc::new(BrowseLimits::new(
+                4,
+                Duration::ZERO,
+                1,
+                Duration::from_secs(1),
+            )),
+            ..BrowseConfig::default()
+        };
+        const CALLS: u32 = 200;
+        let opts = BrowseOptions::default();
+
+        let started = std::time::Instant::now();
+        for _ in 0..CALLS {
+            // A new config has not built its client yet.
+            browse_with_config(&url, &opts, &config()).await.unwrap();
+        }
+        let fresh = started.elapsed() / CALLS;
+
+        let cfg = config();
+        browse_with_config(&url, &opts, &cfg).await.unwrap();
+        let started = std::time::Instant::now();
+        for _ in 0..CALLS {
+            browse_with_config(&url, &opts, &cfg).await.unwrap();
+        }
+        let shared = started.elapsed() / CALLS;
+
+        println!("client per call: {fresh:?} per browse");
+        println!("shared client:   {shared:?} per browse");
     }
 }
@@ -0,0 +1,174 @@
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheStatus {
+    Hit,
+    Miss,
+    Disabled,
+    /// Not in memory, but the origin confirmed the on-disk copy is current.
+    Revalidated,
+}
+
+impl CacheStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CacheStatus::Hit => "hit",
+            CacheStatus::Miss => "miss",
+            CacheStatus::Disabled => "disabled",
+            CacheStatus::Revalidated => "revalidated",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Entry<V> {
+    value: V,
+    expires: Instant,
+    tick: u64,
+}
+
+#[derive(Debug)]
+struct Inner<K, V> {
+    entries: HashMap<K, Entry<V>>,
+    // Last-use tick -> key, oldest first.
+    order: BTreeMap<u64, K>,
+    tick: u64,
+}
+
+/// Bounded in-memory LRU cache whose entries also expire after a fixed TTL.
+#[derive(Debug)]
+pub struct TtlCache<K, V> {
+    inner: Mutex<Inner<K, V>>,
+    capacity: usize,
+    ttl: Duration,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> TtlCache<K, V> {
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                entries: HashMap::new(),
+                order: BTreeMap::new(),
+                tick: 0,
+            }),
+            capacity,
+            ttl,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.capacity > 0 && !self.ttl.is_zero()
+    }
+
+    pub fn get(&self, key: &K) -> Option<V> {
+        self.get_at(key, Instant::now())
+    }
+
+    /// Like [`TtlCache::get`], also reporting whether this was a hit, a miss or a no-op.
+    pub fn lookup(&self, key: &K) -> (Option<V>, CacheStatus) {
+        if !self.is_enabled() {
+            return (None, CacheStatus::Disabled);
+        }
+        match self.get(key) {
+            Some(value) => (Some(value), CacheStatus::Hit),
+            None => (None, CacheStatus::Miss),
+        }
+    }
+
+    pub fn insert(&self, key: K, value: V) {
+        self.insert_at(key, value, Instant::now());
+    }
+
+    fn get_at(&self, key: &K, now: Instant) -> Option<V> {
+        if !self.is_enabled() {
+            return None;
+        }
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let inner = &mut *inner;
+
+        let entry = inner.entries.get_mut(key)?;
+        if entry.expires <= now {
+            inner.order.remove(&entry.tick);
+            inner.entries.remove(key);
+            return None;
+        }
+
+        inner.tick += 1;
+        inner.order.remove(&entry.tick);
+        entry.tick = inner.tick;
+        inner.order.insert(entry.tick, key.clone());
+        Some(entry.value.clone())
+    }
+
+    fn insert_at(&self, key: K, value: V, now: Instant) {
+        if !self.is_enabled() {
+            return;
+        }
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let inner = &mut *inner;
+
+        inner.tick += 1;
+        let tick = inner.tick;
+        if let Some(old) = inner.entries.insert(
+            key.clone(),
+            Entry {
+                value,
+                expires: now + self.ttl,
+                tick,
+            },
+        ) {
+            inner.order.remove(&old.tick);
+        }
+        inner.order.insert(tick, key);
+
+        while inner.entries.len() > self.capacity {
+            let Some((_, oldest)) = inner.order.pop_first() else {
+                break;
+            };
+            inner.entries.remove(&oldest);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let cache = TtlCache::new(2, Duration::from_secs(60));
+        let now = Instant::now();
+        cache.insert_at("a", 1, now);
+        cache.insert_at("b", 2, now);
+        assert_eq!(cache.get_at(&"a", now), Some(1));
+
+        cache.insert_at("c", 3, now);
+        assert_eq!(cache.get_at(&"a", now), Some(1));
+        assert_eq!(cache.get_at(&"b", now), None);
+        assert_eq!(cache.get_at(&"c", now), Some(3));
+    }
+
+    #[test]
+    fn expires_after_ttl() {
+        let cache = TtlCache::new(4, Duration::from_secs(10));
+        let now = Instant::now();
+        cache.insert_at("a", 1, now);
+        assert_eq!(cache.get_at(&"a", now + Duration::from_secs(9)), Some(1));
+        assert_eq!(cache.get_at(&"a", now + Duration::from_secs(10)), None);
+    }
+
+    #[test]
+    fn zero_ttl_or_capacity_disables() {
+        let cache = TtlCache::new(4, Duration::ZERO);
+        cache.insert("a", 1);
+        assert!(!cache.is_enabled());
+        assert_eq!(cache.get(&"a"), None);
+
+        let cache = TtlCache::new(0, Duration::from_secs(10));
+        cache.insert("a", 1);
+        assert_eq!(cache.get(&"a"), None);
+    }
+}
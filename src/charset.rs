@@ -0,0 +1,105 @@
+use encoding_rs::{Encoding, UTF_8};
+use scraper::{Html, Selector};
+
+/// How far into the body to look for `<meta charset>`, as in the HTML spec's prescan.
+const META_PRESCAN_BYTES: usize = 1024;
+
+/// A response body transcoded to UTF-8.
+#[derive(Debug)]
+pub struct Decoded {
+    pub text: String,
+    /// Name of the encoding that was used, e.g. `Shift_JIS`.
+    pub encoding: &'static str,
+    /// Whether malformed sequences were replaced with U+FFFD.
+    pub lossy: bool,
+}
+
+/// Extract the `charset` parameter from a `Content-Type` value.
+fn content_type_charset(content_type: &str) -> Option<&'static Encoding> {
+    content_type.split(';').skip(1).find_map(|param| {
+        let (name, value) = param.split_once('=')?;
+        if !name.trim().eq_ignore_ascii_case("charset") {
+            return None;
+        }
+        Encoding::for_label(value.trim().trim_matches(['"', '\'']).as_bytes())
+    })
+}
+
+/// Find a `<meta charset>` or `<meta http-equiv="Content-Type">` declaration
+/// near the start of the document.
+fn meta_charset(body: &[u8]) -> Option<&'static Encoding> {
+    let prefix = String::from_utf8_lossy(&body[..body.len().min(META_PRESCAN_BYTES)]);
+    let doc = Html::parse_document(&prefix);
+    let selector = Selector::parse("meta").expect("valid selector");
+    let encoding = doc.select(&selector).find_map(|meta| {
+        let meta = meta.value();
+        if let Some(label) = meta.attr("charset") {
+            return Encoding::for_label(label.trim().as_bytes());
+        }
+        let is_content_type = meta
+            .attr("http-equiv")
+            .is_some_and(|v| v.trim().eq_ignore_ascii_case("content-type"));
+        if is_content_type {
+            return meta.attr("content").and_then(content_type_charset);
+        }
+        None
+    })?;
+    // An ASCII-compatible prescan cannot have found a real UTF-16 declaration.
+    if encoding == encoding_rs::UTF_16LE || encoding == encoding_rs::UTF_16BE {
+        return Some(UTF_8);
+    }
+    Some(encoding)
+}
+
+/// Decode an HTML body, honoring (in order) a byte-order mark, the
+/// `Content-Type` charset and an in-document `<meta>` declaration, and
+/// defaulting to UTF-8.
+pub fn decode_html(body: &[u8], content_type: Option<&str>) -> Decoded {
+    let declared = content_type
+        .and_then(content_type_charset)
+        .or_else(|| meta_charset(body))
+        .unwrap_or(UTF_8);
+    // `decode` lets a BOM override the declared encoding.
+    let (text, used, lossy) = declared.decode(body);
+    Decoded {
+        text: text.into_owned(),
+        encoding: used.name(),
+        lossy,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefers_bom_then_header_then_meta() {
+        let (sjis, _, _) = encoding_rs::SHIFT_JIS.encode("<p>日本語</p>");
+        let decoded = decode_html(&sjis, Some("text/html; charset=\"Shift_JIS\""));
+        assert_eq!(decoded.text, "<p>日本語</p>");
+        assert_eq!(decoded.encoding, "Shift_JIS");
+        assert!(!decoded.lossy);
+
+        let mut html = b"<html><head><meta http-equiv=\"Content-Type\" content=\"text/html; charset=windows-1251\"></head><body>".to_vec();
+        html.extend_from_slice(&encoding_rs::WINDOWS_1251.encode("Привет").0);
+        let decoded = decode_html(&html, Some("text/html"));
+        assert!(decoded.text.ends_with("Привет"));
+        assert_eq!(decoded.encoding, "windows-1251");
+
+        let html = b"<meta charset=iso-8859-1><p>caf\xe9</p>";
+        assert!(decode_html(html, None).text.contains("café"));
+
+        let mut bom = b"\xef\xbb\xbf".to_vec();
+        bom.extend_from_slice("<p>é</p>".as_bytes());
+        let decoded = decode_html(&bom, Some("text/html; charset=iso-8859-1"));
+        assert_eq!(decoded.text, "<p>é</p>");
+        assert_eq!(decoded.encoding, "UTF-8");
+    }
+
+    #[test]
+    fn undeclared_invalid_utf8_is_lossy() {
+        let decoded = decode_html(b"<p>caf\xe9</p>", None);
+        assert!(decoded.lossy);
+        assert_eq!(decoded.text, "<p>caf\u{fffd}</p>");
+    }
+}
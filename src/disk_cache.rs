@@ -0,0 +1,234 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+
+const META_EXT: &str = "json";
+const BODY_EXT: &str = "body";
+
+/// Validators and headers stored next to a cached response body.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CachedMeta {
+    pub url: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub content_type: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct CachedResponse {
+    pub meta: CachedMeta,
+    pub body: Vec<u8>,
+}
+
+/// Response bodies on disk, keyed by URL, for revalidation with
+/// `If-None-Match`/`If-Modified-Since`.
+///
+/// Each entry is a `<sha256>.json` metadata file and a `<sha256>.body` file.
+/// The metadata file's mtime records the last use; once the directory grows
+/// past `max_bytes`, least recently used entries are removed first.
+#[derive(Debug, Clone)]
+pub struct DiskCache {
+    dir: PathBuf,
+    max_bytes: u64,
+}
+
+impl DiskCache {
+    pub fn new(dir: PathBuf, max_bytes: u64) -> Self {
+        Self { dir, max_bytes }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn paths(&self, url: &str) -> (PathBuf, PathBuf) {
+        let key = format!("{:x}", Sha256::digest(url.as_bytes()));
+        (
+            self.dir.join(format!("{key}.{META_EXT}")),
+            self.dir.join(format!("{key}.{BODY_EXT}")),
+        )
+    }
+
+    /// Read the entry for `url`, marking it as recently used.
+    pub fn load(&self, url: &str) -> Option<CachedResponse> {
+        let (meta_path, body_path) = self.paths(url);
+        let meta: CachedMeta = serde_json::from_slice(&fs::read(&meta_path).ok()?).ok()?;
+        if meta.url != url {
+            return None;
+        }
+        let body = fs::read(&body_path).ok()?;
+        if let Ok(file) = fs::File::options().append(true).open(&meta_path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        Some(CachedResponse { meta, body })
+    }
+
+    /// Write an entry, then evict old entries if the cache is over its cap.
+    /// Bodies larger than the whole cap are not stored.
+    pub fn store(&self, meta: &CachedMeta, body: &[u8]) -> Result<()> {
+        if body.len() as u64 > self.max_bytes {
+            return Ok(());
+        }
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("failed to create {}", self.dir.display()))?;
+        let (meta_path, body_path) = self.paths(&meta.url);
+        // Write to temporary names and rename, so readers never see half an entry.
+        let suffix = fastrand::u64(..);
+        let tmp = |path: &Path| PathBuf::from(format!("{}.tmp{suffix:x}", path.display()));
+        let (meta_tmp, body_tmp) = (tmp(&meta_path), tmp(&body_path));
+        fs::write(&body_tmp, body).context("failed to write cached body")?;
+        fs::write(&meta_tmp, serde_json::to_vec(meta)?).context("failed to write cache entry")?;
+        fs::rename(&body_tmp, &body_path).context("failed to store cached body")?;
+        fs::rename(&meta_tmp, &meta_path).context("failed to store cache entry")?;
+        self.evict()
+    }
+
+    fn evict(&self) -> Result<()> {
+        struct Entry {
+            meta: PathBuf,
+            size: u64,
+            used: SystemTime,
+        }
+
+        let mut entries = Vec::new();
+        let mut total = 0;
+        for item in fs::read_dir(&self.dir)? {
+            let path = item?.path();
+            if path.extension().is_none_or(|ext| ext != META_EXT) {
+                continue;
+            }
+            let Ok(meta) = fs::metadata(&path) else {
+                continue;
+            };
+            let body_size = fs::metadata(path.with_extension(BODY_EXT)).map_or(0, |m| m.len());
+            let size = meta.len() + body_size;
+            total += size;
+            entries.push(Entry {
+                used: meta.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                meta: path,
+                size,
+            });
+        }
+
+        entries.sort_by_key(|entry| entry.used);
+        for entry in entries {
+            if total <= self.max_bytes {
+                break;
+            }
+            remove_if_exists(&entry.meta)?;
+            remove_if_exists(&entry.meta.with_extension(BODY_EXT))?;
+            total -= entry.size;
+        }
+        Ok(())
+    }
+
+    /// Delete every entry; returns how many there were.
+    pub fn clear(&self) -> Result<usize> {
+        let items = match fs::read_dir(&self.dir) {
+            Ok(items) => items,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(0),
+            Err(e) => {
+                return Err(e).with_context(|| format!("failed to read {}", self.dir.display()));
+            }
+        };
+        let mut removed = 0;
+        for item in items {
+            let path = item?.path();
+            let Some(ext) = path.extension().and_then(|ext| ext.to_str()) else {
+                continue;
+            };
+            if ext == META_EXT {
+                removed += 1;
+            }
+            if ext == META_EXT || ext == BODY_EXT || ext.starts_with("tmp") {
+                remove_if_exists(&path)?;
+            }
+        }
+        Ok(removed)
+    }
+}
+
+fn remove_if_exists(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != ErrorKind::NotFound => {
+            Err(e).with_context(|| format!("failed to remove {}", path.display()))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) fn temp_cache(name: &str, max_bytes: u64) -> DiskCache {
+        let dir = std::env::temp_dir().join(format!(
+            "searxng-mcp-disk-cache-{name}-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        DiskCache::new(dir, max_bytes)
+    }
+
+    fn meta(url: &str) -> CachedMeta {
+        CachedMeta {
+            url: url.to_string(),
+            etag: Some("\"v1\"".to_string()),
+            last_modified: None,
+            content_type: Some("text/html".to_string()),
+        }
+    }
+
+    #[test]
+    fn stores_loads_and_clears() {
+        let cache = temp_cache("roundtrip", 1_000_000);
+        assert!(cache.load("https://example.com/").is_none());
+        cache
+            .store(&meta("https://example.com/"), b"<p>hello</p>")
+            .unwrap();
+
+        let hit = cache.load("https://example.com/").unwrap();
+        assert_eq!(hit.meta, meta("https://example.com/"));
+        assert_eq!(hit.body, b"<p>hello</p>");
+        assert!(cache.load("https://example.com/other").is_none());
+
+        assert_eq!(cache.clear().unwrap(), 1);
+        assert!(cache.load("https://example.com/").is_none());
+        fs::remove_dir_all(cache.dir()).unwrap();
+    }
+
+    #[test]
+    fn evicts_least_recently_used_over_cap() {
+        let cache = temp_cache("evict", 700);
+        let body = [b'x'; 200];
+        let set_used = |url: &str, secs: u64| {
+            let file = fs::File::options()
+                .append(true)
+                .open(cache.paths(url).0)
+                .unwrap();
+            file.set_modified(SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(secs))
+                .unwrap();
+        };
+
+        cache.store(&meta("https://a.example/"), &body).unwrap();
+        set_used("https://a.example/", 1);
+        cache.store(&meta("https://b.example/"), &body).unwrap();
+        set_used("https://b.example/", 3);
+        cache.load("https://a.example/").unwrap();
+        cache.store(&meta("https://c.example/"), &body).unwrap();
+
+        assert!(cache.load("https://a.example/").is_some());
+        assert!(cache.load("https://b.example/").is_none());
+        assert!(cache.load("https://c.example/").is_some());
+
+        cache
+            .store(&meta("https://big.example/"), &[0; 701])
+            .unwrap();
+        assert!(cache.load("https://big.example/").is_none());
+        fs::remove_dir_all(cache.dir()).unwrap();
+    }
+}
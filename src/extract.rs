@@ -0,0 +1,224 @@
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use ego_tree::NodeId;
+use regex::Regex;
+use scraper::{ElementRef, Html, Node};
+
+/// Paragraphs shorter than this are not worth scoring.
+const MIN_PARAGRAPH_CHARS: usize = 25;
+/// Below this the best candidate is probably not an article; keep the full page.
+const MIN_ARTICLE_CHARS: usize = 250;
+
+static POSITIVE_HINT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)article|body|content|entry|main|page|post|text|blog|story")
+        .expect("valid regex")
+});
+static NEGATIVE_HINT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)comment|meta|footer|footnote|nav|sidebar|banner|cookie|consent|menu|share|social|sponsor|promo|related|subscribe|newsletter|popup|modal|breadcrumb|masthead|header|\bads?\b",
+    )
+    .expect("valid regex")
+});
+
+fn is_hidden_text_container(name: &str) -> bool {
+    matches!(name, "script" | "style" | "noscript" | "template")
+}
+
+fn is_boilerplate_container(name: &str) -> bool {
+    matches!(name, "nav" | "aside" | "footer" | "header" | "form")
+}
+
+/// Length in chars of the visible text under `el`, and of the part inside links.
+fn text_stats(el: ElementRef<'_>) -> (usize, usize) {
+    let mut total = 0;
+    let mut linked = 0;
+    for node in el.descendants() {
+        let Node::Text(text) = node.value() else {
+            continue;
+        };
+        let mut in_link = false;
+        let mut hidden = false;
+        for ancestor in node.ancestors().filter_map(ElementRef::wrap) {
+            let name = ancestor.value().name();
+            hidden |= is_hidden_text_container(name);
+            in_link |= name == "a";
+            if ancestor.id() == el.id() {
+                break;
+            }
+        }
+        if hidden {
+            continue;
+        }
+        let len = text
+            .split_whitespace()
+            .map(|w| w.chars().count() + 1)
+            .sum::<usize>();
+        total += len;
+        if in_link {
+            linked += len;
+        }
+    }
+    (total, linked)
+}
+
+fn class_weight(el: ElementRef<'_>) -> f64 {
+    let mut weight = 0.0;
+    for hint in [el.value().attr("class"), el.value().attr("id")]
+        .into_iter()
+        .flatten()
+    {
+        if NEGATIVE_HINT.is_match(hint) {
+            weight -= 25.0;
+        }
+        if POSITIVE_HINT.is_match(hint) {
+            weight += 25.0;
+        }
+    }
+    weight
+}
+
+fn initial_score(el: ElementRef<'_>) -> f64 {
+    let tag = match el.value().name() {
+        "div" | "article" | "section" | "main" => 5.0,
+        "pre" | "td" | "blockquote" => 3.0,
+        "address" | "ol" | "ul" | "dl" | "dd" | "dt" | "li" | "form" => -3.0,
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "th" => -5.0,
+        _ => 0.0,
+    };
+    tag + class_weight(el)
+}
+
+fn link_density(el: ElementRef<'_>) -> f64 {
+    let (total, linked) = text_stats(el);
+    if total == 0 {
+        return 1.0;
+    }
+    linked as f64 / total as f64
+}
+
+/// Find the main content of a page, Readability style.
+///
+/// Paragraph-like blocks award points (for length and commas) to their
+/// parent and, diminishing, to two further ancestors. Candidates are weighted
+/// by tag and class/id hints, penalized by link density, and the winner is
+/// returned as HTML together with well-scoring siblings. Returns `None` when
+/// nothing looks like an article, in which case callers should keep the page.
+pub fn main_content_html(html: &str) -> Option<String> {
+    let doc = Html::parse_document(html);
+    let mut scores: HashMap<NodeId, f64> = HashMap::new();
+
+    for el in doc
+        .root_element()
+        .descendants()
+        .filter_map(ElementRef::wrap)
+    {
+        if !matches!(el.value().name(), "p" | "pre" | "td" | "blockquote") {
+            continue;
+        }
+        if el
+            .ancestors()
+            .filter_map(ElementRef::wrap)
+            .any(|a| is_boilerplate_container(a.value().name()))
+        {
+            continue;
+        }
+        let text: String = el.text().collect();
+        let len = text.trim().chars().count();
+        if len < MIN_PARAGRAPH_CHARS {
+            continue;
+        }
+        let score = 1.0 + text.matches(',').count() as f64 + (len as f64 / 100.0).min(3.0);
+
+        for (level, ancestor) in el
+            .ancestors()
+            .filter_map(ElementRef::wrap)
+            .take(3)
+            .enumerate()
+        {
+            let divider = match level {
+                0 => 1.0,
+                1 => 2.0,
+                n => n as f64 * 3.0,
+            };
+            *scores
+                .entry(ancestor.id())
+                .or_insert_with(|| initial_score(ancestor)) += score / divider;
+        }
+    }
+
+    let adjusted: HashMap<NodeId, f64> = scores
+        .into_iter()
+        .filter_map(|(id, score)| {
+            let el = ElementRef::wrap(doc.tree.get(id)?)?;
+            Some((id, score * (1.0 - link_density(el))))
+        })
+        .collect();
+    let (&top_id, &top_score) = adjusted
+        .iter()
+        .max_by(|a, b| a.1.total_cmp(b.1).then_with(|| b.0.cmp(a.0)))?;
+    let top = ElementRef::wrap(doc.tree.get(top_id)?)?;
+    if text_stats(top).0 < MIN_ARTICLE_CHARS {
+        return None;
+    }
+
+    // Articles are often split across sibling containers; keep the ones that score well.
+    let threshold = (top_score * 0.2).max(10.0);
+    let Some(parent) = top.parent() else {
+        return Some(top.html());
+    };
+    let parts: Vec<String> = parent
+        .children()
+        .filter_map(ElementRef::wrap)
+        .filter(|sibling| {
+            if sibling.id() == top_id {
+                return true;
+            }
+            if adjusted.get(&sibling.id()).is_some_and(|&s| s >= threshold) {
+                return true;
+            }
+            if sibling.value().name() == "p" {
+                let (total, linked) = text_stats(*sibling);
+                return total > 80 && (linked as f64) < total as f64 * 0.25;
+            }
+            false
+        })
+        .map(|el| el.html())
+        .collect();
+    Some(parts.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARTICLE: &str = r#"<html><body>
+        <nav class="menu"><a href="/">Home</a> <a href="/about">About</a> <a href="/blog">Blog</a></nav>
+        <div id="cookie-banner"><p>We use cookies, to improve your experience, please accept them all.</p></div>
+        <div class="sidebar"><ul><li><a href="/a">Related one</a></li><li><a href="/b">Related two</a></li></ul></div>
+        <div class="post-content">
+          <h1>Ownership in Rust</h1>
+          <p>Ownership is a set of rules that govern how a Rust program manages memory, without a garbage collector.</p>
+          <p>Each value has an owner, there can only be one owner at a time, and the value is dropped when the owner goes out of scope.</p>
+          <p>Borrowing lets code refer to a value without taking ownership, which keeps the rules practical in everyday code.</p>
+        </div>
+        <footer><p>Copyright 2024, Example Corp, all rights reserved, see our terms and privacy policy.</p></footer>
+    </body></html>"#;
+
+    #[test]
+    fn keeps_article_and_drops_chrome() {
+        let main = main_content_html(ARTICLE).expect("article found");
+        assert!(main.contains("Ownership is a set of rules"));
+        assert!(main.contains("Borrowing lets code"));
+        assert!(!main.contains("About"));
+        assert!(!main.contains("cookies"));
+        assert!(!main.contains("Related one"));
+        assert!(!main.contains("Copyright"));
+    }
+
+    #[test]
+    fn returns_none_without_article_text() {
+        let html = r#"<html><body><nav><a href="/">Home</a></nav><p>Short.</p></body></html>"#;
+        assert!(main_content_html(html).is_none());
+    }
+}
@@ -0,0 +1,172 @@
+use anyhow::{Result, anyhow};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scope {
+    /// `example.com`
+    Exact,
+    /// `*.example.com`: subdomains only.
+    Subdomains,
+    /// `.example.com`: the domain and its subdomains.
+    DomainAndSubdomains,
+}
+
+/// One entry of `BROWSE_ALLOWED_HOSTS` or `BROWSE_DENIED_HOSTS`.
+///
+/// `example.com` matches that host only, `*.example.com` any subdomain of it
+/// and `.example.com` the domain plus its subdomains. A `:port` suffix (e.g.
+/// `example.com:8443`, `[::1]:8080`) restricts the entry to that port;
+/// otherwise it applies to every port.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostPattern {
+    rule: String,
+    host: String,
+    scope: Scope,
+    port: Option<u16>,
+}
+
+impl HostPattern {
+    pub fn parse(rule: &str) -> Result<Self> {
+        let rule = rule.trim().to_ascii_lowercase();
+        let invalid = |why: &str| anyhow!("invalid host pattern '{rule}': {why}");
+
+        let (host, port) = if let Some(rest) = rule.strip_prefix('[') {
+            let (host, after) = rest
+                .split_once(']')
+                .ok_or_else(|| invalid("unclosed '['"))?;
+            match after.strip_prefix(':') {
+                Some(port) => (host, Some(port)),
+                None if after.is_empty() => (host, None),
+                None => return Err(invalid("unexpected text after ']'")),
+            }
+        } else {
+            match rule.rsplit_once(':') {
+                Some((host, port)) if !host.contains(':') => (host, Some(port)),
+                // A bare IPv6 address.
+                Some(_) => (rule.as_str(), None),
+                None => (rule.as_str(), None),
+            }
+        };
+        let port = port
+            .map(|port| port.parse::<u16>().map_err(|_| invalid("bad port")))
+            .transpose()?;
+
+        let (scope, host) = if let Some(domain) = host.strip_prefix("*.") {
+            (Scope::Subdomains, domain)
+        } else if let Some(domain) = host.strip_prefix('.') {
+            (Scope::DomainAndSubdomains, domain)
+        } else {
+            (Scope::Exact, host)
+        };
+        let host = host.trim_end_matches('.');
+        if host.is_empty() {
+            return Err(invalid("no host"));
+        }
+        if host.contains('*') {
+            return Err(invalid("'*' is only supported as a leading '*.' label"));
+        }
+
+        Ok(Self {
+            host: host.to_string(),
+            scope,
+            port,
+            rule,
+        })
+    }
+
+    /// Whether `host` (as in a URL, IPv6 in brackets or not) on `port` matches.
+    pub fn matches(&self, host: &str, port: Option<u16>) -> bool {
+        if self.port.is_some() && self.port != port {
+            return false;
+        }
+        let host = host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .trim_end_matches('.')
+            .to_ascii_lowercase();
+        let is_subdomain = || {
+            host.strip_suffix(self.host.as_str())
+                .is_some_and(|label| label.len() > 1 && label.ends_with('.'))
+        };
+        match self.scope {
+            Scope::Exact => host == self.host,
+            Scope::Subdomains => is_subdomain(),
+            Scope::DomainAndSubdomains => host == self.host || is_subdomain(),
+        }
+    }
+}
+
+impl std::fmt::Display for HostPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.rule)
+    }
+}
+
+/// Parse a comma-separated list of host patterns from `var`.
+pub fn parse_host_patterns(var: &str, value: &str) -> Result<Vec<HostPattern>> {
+    value
+        .split(',')
+        .filter(|v| !v.trim().is_empty())
+        .map(|v| HostPattern::parse(v).map_err(|e| anyhow!("{var}: {e}")))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_exact_wildcard_and_suffix_patterns() {
+        let cases: &[(&str, &str, Option<u16>, bool)] = &[
+            ("example.com", "example.com", Some(443), true),
+            ("example.com", "EXAMPLE.com.", Some(80), true),
+            ("example.com", "www.example.com", Some(443), false),
+            ("*.example.com", "docs.example.com", Some(443), true),
+            ("*.example.com", "a.b.example.com", Some(443), true),
+            ("*.example.com", "example.com", Some(443), false),
+            ("*.example.com", "badexample.com", Some(443), false),
+            (".example.com", "example.com", Some(443), true),
+            (".example.com", "docs.example.com", Some(443), true),
+            (".example.com", "notexample.com", Some(443), false),
+            ("example.com:8443", "example.com", Some(8443), true),
+            ("example.com:8443", "example.com", Some(443), false),
+            ("*.example.com:443", "api.example.com", Some(443), true),
+            ("[::1]:8080", "[::1]", Some(8080), true),
+            ("[::1]", "[::1]", Some(80), true),
+            ("::1", "[::1]", Some(80), true),
+            ("127.0.0.1:8080", "127.0.0.1", Some(8081), false),
+        ];
+        for (rule, host, port, want) in cases {
+            let pattern = HostPattern::parse(rule).unwrap();
+            assert_eq!(
+                pattern.matches(host, *port),
+                *want,
+                "{rule} vs {host}:{port:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_patterns() {
+        for rule in [
+            "*",
+            "*.",
+            "ex*ample.com",
+            "example.com:http",
+            "[::1",
+            "[::1]x",
+        ] {
+            assert!(HostPattern::parse(rule).is_err(), "{rule}");
+        }
+        let err = parse_host_patterns("BROWSE_DENIED_HOSTS", "ok.example, a.*.b").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "BROWSE_DENIED_HOSTS: invalid host pattern 'a.*.b': '*' is only supported as a leading '*.' label"
+        );
+        assert_eq!(
+            HostPattern::parse(" *.Example.com:443 ")
+                .unwrap()
+                .to_string(),
+            "*.example.com:443"
+        );
+    }
+}
@@ -0,0 +1,197 @@
+use std::net::IpAddr;
+use std::sync::LazyLock;
+
+use anyhow::{Result, anyhow};
+use ipnet::IpNet;
+
+/// Ranges browse never connects to unless `BROWSE_ALLOW_CIDRS` (or
+/// `BROWSE_ALLOW_PRIVATE`) says otherwise: everything that is not ordinary
+/// public unicast, plus cloud metadata endpoints that are.
+const DEFAULT_DENY: &[&str] = &[
+    // IPv4
+    "0.0.0.0/8",        // "this network"
+    "10.0.0.0/8",       // private
+    "100.64.0.0/10",    // carrier-grade NAT; also Alibaba Cloud metadata (100.100.100.200)
+    "127.0.0.0/8",      // loopback
+    "169.254.0.0/16",   // link-local; AWS/GCP/Azure/OpenStack metadata (169.254.169.254)
+    "172.16.0.0/12",    // private
+    "192.0.0.0/24",     // IETF protocol assignments; Oracle Cloud metadata (192.0.0.192)
+    "192.0.2.0/24",     // TEST-NET-1
+    "192.88.99.0/24",   // 6to4 relay anycast
+    "192.168.0.0/16",   // private
+    "198.18.0.0/15",    // benchmarking
+    "198.51.100.0/24",  // TEST-NET-2
+    "203.0.113.0/24",   // TEST-NET-3
+    "224.0.0.0/4",      // multicast
+    "240.0.0.0/4",      // reserved, including 255.255.255.255 broadcast
+    "168.63.129.16/32", // Azure WireServer
+    // IPv6 (IPv4-mapped addresses are checked as IPv4)
+    "::/96",          // unspecified, loopback and IPv4-compatible
+    "64:ff9b::/96",   // NAT64
+    "64:ff9b:1::/48", // local-use NAT64
+    "100::/64",       // discard-only
+    "2001::/32",      // Teredo
+    "2001:2::/48",    // benchmarking
+    "2001:db8::/32",  // documentation
+    "2002::/16",      // 6to4
+    "3fff::/20",      // documentation
+    "fc00::/7",       // unique local; AWS metadata (fd00:ec2::254)
+    "fe80::/10",      // link-local
+    "fec0::/10",      // site-local (deprecated)
+    "ff00::/8",       // multicast
+];
+
+static DEFAULT_DENY_NETS: LazyLock<Vec<IpNet>> = LazyLock::new(|| {
+    DEFAULT_DENY
+        .iter()
+        .map(|net| net.parse().expect("valid built-in CIDR"))
+        .collect()
+});
+
+/// Which resolved addresses browse may connect to.
+///
+/// An address is refused when it falls in a deny range (the built-in set
+/// and/or `BROWSE_DENY_CIDRS`) and in no `BROWSE_ALLOW_CIDRS` range, so allow
+/// entries carve exceptions out of the deny set.
+#[derive(Debug, Clone)]
+pub struct IpPolicy {
+    builtin: bool,
+    deny: Vec<IpNet>,
+    allow: Vec<IpNet>,
+}
+
+impl Default for IpPolicy {
+    fn default() -> Self {
+        Self::new(true, Vec::new(), Vec::new())
+    }
+}
+
+impl IpPolicy {
+    pub fn new(builtin: bool, deny: Vec<IpNet>, allow: Vec<IpNet>) -> Self {
+        Self {
+            builtin,
+            deny,
+            allow,
+        }
+    }
+
+    /// True when no address can be denied, so there is nothing to check.
+    pub fn is_permissive(&self) -> bool {
+        !self.builtin && self.deny.is_empty()
+    }
+
+    /// The deny range `ip` falls in, or `None` if it may be connected to.
+    pub fn denied_by(&self, ip: IpAddr) -> Option<IpNet> {
+        let ip = ip.to_canonical();
+        if self.allow.iter().any(|net| net.contains(&ip)) {
+            return None;
+        }
+        let builtin = if self.builtin {
+            DEFAULT_DENY_NETS.as_slice()
+        } else {
+            &[]
+        };
+        builtin
+            .iter()
+            .chain(&self.deny)
+            .find(|net| net.contains(&ip))
+            .copied()
+    }
+}
+
+/// Parse a comma-separated list of CIDRs; a bare address is a single-host range.
+pub fn parse_cidrs(var: &str, value: &str) -> Result<Vec<IpNet>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(|v| {
+            v.parse::<IpNet>()
+                .or_else(|_| v.parse::<IpAddr>().map(IpNet::from))
+                .map(|net| net.trunc())
+                .map_err(|_| anyhow!("invalid CIDR '{v}' in {var}"))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_deny_set() {
+        let policy = IpPolicy::default();
+        let cases: &[(&str, Option<&str>)] = &[
+            ("8.8.8.8", None),
+            ("93.184.216.34", None),
+            ("0.0.0.0", Some("0.0.0.0/8")),
+            ("10.1.2.3", Some("10.0.0.0/8")),
+            ("100.100.100.200", Some("100.64.0.0/10")),
+            ("127.0.0.1", Some("127.0.0.0/8")),
+            ("169.254.169.254", Some("169.254.0.0/16")),
+            ("172.31.255.255", Some("172.16.0.0/12")),
+            ("172.32.0.1", None),
+            ("192.0.0.192", Some("192.0.0.0/24")),
+            ("192.168.1.1", Some("192.168.0.0/16")),
+            ("198.19.0.1", Some("198.18.0.0/15")),
+            ("224.0.0.251", Some("224.0.0.0/4")),
+            ("239.255.255.250", Some("224.0.0.0/4")),
+            ("250.1.1.1", Some("240.0.0.0/4")),
+            ("255.255.255.255", Some("240.0.0.0/4")),
+            ("168.63.129.16", Some("168.63.129.16/32")),
+            ("::", Some("::/96")),
+            ("::1", Some("::/96")),
+            ("::ffff:127.0.0.1", Some("127.0.0.0/8")),
+            ("::ffff:169.254.169.254", Some("169.254.0.0/16")),
+            ("::ffff:8.8.8.8", None),
+            ("64:ff9b::a00:1", Some("64:ff9b::/96")),
+            ("2001:0:4136:e378::1", Some("2001::/32")),
+            ("2002:7f00:1::1", Some("2002::/16")),
+            ("fc00::1", Some("fc00::/7")),
+            ("fd00:ec2::254", Some("fc00::/7")),
+            ("fe80::1", Some("fe80::/10")),
+            ("ff02::1", Some("ff00::/8")),
+            ("2606:4700:4700::1111", None),
+        ];
+        for (ip, want) in cases {
+            let got = policy
+                .denied_by(ip.parse().unwrap())
+                .map(|net| net.to_string());
+            assert_eq!(got.as_deref(), *want, "{ip}");
+        }
+    }
+
+    #[test]
+    fn configured_ranges() {
+        let deny = parse_cidrs("BROWSE_DENY_CIDRS", "203.0.114.0/24, 2600::1").unwrap();
+        let allow = parse_cidrs("BROWSE_ALLOW_CIDRS", "10.20.0.0/16,fd12:3456::/32").unwrap();
+        let cases: &[(bool, &str, Option<&str>)] = &[
+            (true, "10.20.1.1", None),
+            (true, "10.21.1.1", Some("10.0.0.0/8")),
+            (true, "fd12:3456::1", None),
+            (true, "203.0.114.9", Some("203.0.114.0/24")),
+            (true, "2600::1", Some("2600::1/128")),
+            (true, "2600::2", None),
+            (false, "127.0.0.1", None),
+            (false, "203.0.114.9", Some("203.0.114.0/24")),
+        ];
+        for (builtin, ip, want) in cases {
+            let policy = IpPolicy::new(*builtin, deny.clone(), allow.clone());
+            let got = policy
+                .denied_by(ip.parse().unwrap())
+                .map(|net| net.to_string());
+            assert_eq!(got.as_deref(), *want, "{ip} (builtin: {builtin})");
+        }
+
+        assert!(IpPolicy::new(false, Vec::new(), allow).is_permissive());
+        assert_eq!(
+            parse_cidrs("BROWSE_DENY_CIDRS", "10.1.2.3/8").unwrap()[0].to_string(),
+            "10.0.0.0/8"
+        );
+        let err = parse_cidrs("BROWSE_DENY_CIDRS", "10.0.0.0/33").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid CIDR '10.0.0.0/33' in BROWSE_DENY_CIDRS"
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Returned when a browse could not start within the queue wait.
+#[derive(Debug)]
+pub struct BrowseBusy {
+    reason: String,
+}
+
+impl BrowseBusy {
+    pub fn new(reason: String) -> Self {
+        Self { reason }
+    }
+}
+
+impl std::fmt::Display for BrowseBusy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "browse is busy: {}; try again later", self.reason)
+    }
+}
+
+impl std::error::Error for BrowseBusy {}
+
+/// Bounds on how many browses run at once and how often one host is fetched.
+///
+/// Concurrency is a semaphore; per-host politeness is a token bucket (GCRA)
+/// that refills one request every `host_interval` and holds up to
+/// `host_burst`. Callers queue for up to `queue_wait`, then get [`BrowseBusy`].
+#[derive(Debug)]
+pub struct BrowseLimits {
+    slots: Arc<Semaphore>,
+    max_concurrent: usize,
+    host_interval: Duration,
+    host_burst: u32,
+    queue_wait: Duration,
+    // Host -> theoretical arrival time of its next request.
+    hosts: Mutex<HashMap<String, Instant>>,
+}
+
+impl BrowseLimits {
+    pub fn new(
+        max_concurrent: usize,
+        host_interval: Duration,
+        host_burst: u32,
+        queue_wait: Duration,
+    ) -> Self {
+        Self {
+            slots: Arc::new(Semaphore::new(max_concurrent)),
+            max_concurrent,
+            host_interval,
+            host_burst: host_burst.max(1),
+            queue_wait,
+            hosts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Wait for one of the concurrent browse slots; hold the permit for the whole
+    /// call. The permit is owned so it can move into work that outlives the
+    /// caller's future, such as an Obscura thread.
+    pub async fn acquire(&self) -> Result<OwnedSemaphorePermit> {
+        match tokio::time::timeout(self.queue_wait, self.slots.clone().acquire_owned()).await {
+            Ok(permit) => Ok(permit?),
+            Err(_) => Err(BrowseBusy {
+                reason: format!(
+                    "all {} browse slots stayed in use for {} ms",
+                    self.max_concurrent,
+                    self.queue_wait.as_millis()
+                ),
+            }
+            .into()),
+        }
+    }
+
+    /// How long a browse may queue for a slot or a host before giving up.
+    pub fn queue_wait(&self) -> Duration {
+        self.queue_wait
+    }
+
+    /// Wait until `host` may be fetched again.
+    pub async fn wait_for_host(&self, host: &str) -> Result<()> {
+        let wait = self
+            .reserve_host(host, Instant::now())
+            .map_err(|wait| BrowseBusy {
+                reason: format!(
+                    "{host} is rate limited for another {} ms (BROWSE_HOST_INTERVAL_MS={}, BROWSE_HOST_BURST={})",
+                    wait.as_millis(),
+                    self.host_interval.as_millis(),
+                    self.host_burst
+                ),
+            })?;
+        if !wait.is_zero() {
+            tracing::debug!(
+                host,
+                wait_ms = wait.as_millis(),
+                "browse waiting for host rate limit"
+            );
+            tokio::time::sleep(wait).await;
+        }
+        Ok(())
+    }
+
+    /// Claim the host's next request slot; `Err` carries the wait that was too long.
+    fn reserve_host(&self, host: &str, now: Instant) -> Result<Duration, Duration> {
+        if self.host_interval.is_zero() {
+            return Ok(Duration::ZERO);
+        }
+        let host = host.to_ascii_lowercase();
+        let tolerance = self.host_interval * (self.host_burst - 1);
+        let mut hosts = self.hosts.lock().unwrap_or_else(|e| e.into_inner());
+        hosts.retain(|_, tat| *tat > now);
+
+        let tat = hosts.get(&host).copied().unwrap_or(now).max(now);
+        let wait = tat.duration_since(now).saturating_sub(tolerance);
+        if wait > self.queue_wait {
+            return Err(wait);
+        }
+        hosts.insert(host, tat + self.host_interval);
+        Ok(wait)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn host_bucket_allows_burst_then_spaces_requests() {
+        let limits = BrowseLimits::new(4, Duration::from_millis(500), 3, Duration::from_secs(1));
+        let now = Instant::now();
+        let ms = Duration::from_millis;
+        assert_eq!(limits.reserve_host("a.example", now), Ok(ms(0)));
+        assert_eq!(limits.reserve_host("a.example", now), Ok(ms(0)));
+        assert_eq!(limits.reserve_host("a.example", now), Ok(ms(0)));
+        assert_eq!(limits.reserve_host("a.example", now), Ok(ms(500)));
+        assert_eq!(limits.reserve_host("a.example", now), Ok(ms(1000)));
+        assert_eq!(limits.reserve_host("a.example", now), Err(ms(1500)));
+        assert_eq!(limits.reserve_host("b.example", now), Ok(ms(0)));
+        assert_eq!(limits.reserve_host("A.example", now + ms(3000)), Ok(ms(0)));
+    }
+
+    #[tokio::test]
+    async fn concurrency_limit_reports_busy() {
+        let limits = BrowseLimits::new(1, Duration::ZERO, 1, Duration::from_millis(20));
+        let held = limits.acquire().await.unwrap();
+        let err = limits.acquire().await.unwrap_err();
+        assert!(err.downcast_ref::<BrowseBusy>().is_some());
+        assert!(err.to_string().contains("browse is busy"));
+        drop(held);
+        assert!(limits.acquire().await.is_ok());
+    }
+}
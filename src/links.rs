@@ -0,0 +1,198 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use reqwest::Url;
+use scraper::{Html, Selector};
+
+/// An anchor on a page, resolved to an absolute URL.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, schemars::JsonSchema)]
+pub struct PageLink {
+    pub url: String,
+    #[schemars(description = "Anchor text, or the title/aria-label if the anchor has no text")]
+    pub text: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rel: Vec<String>,
+    #[schemars(
+        description = "Whether the link is an http(s) URL on a different host than the page; mailto:, tel: and other non-web links are never external"
+    )]
+    pub external: bool,
+}
+
+/// Collect `<a href>` anchors in document order, resolving them against
+/// `<base href>` if present, else against `page_url`.
+pub fn extract_links(html: &str, page_url: &Url) -> Vec<PageLink> {
+    let doc = Html::parse_document(html);
+    let base_sel = Selector::parse("base[href]").expect("valid selector");
+    let anchor_sel = Selector::parse("a[href]").expect("valid selector");
+
+    let base = doc
+        .select(&base_sel)
+        .next()
+        .and_then(|el| el.value().attr("href"))
+        .and_then(|href| page_url.join(href.trim()).ok())
+        .unwrap_or_else(|| page_url.clone());
+    let page_host = page_url.host_str().map(str::to_ascii_lowercase);
+
+    doc.select(&anchor_sel)
+        .filter_map(|el| {
+            let href = el.value().attr("href")?.trim();
+            let url = base.join(href).ok()?;
+            if matches!(url.scheme(), "javascript" | "data") {
+                return None;
+            }
+            let mut text = el.text().collect::<String>();
+            if text.trim().is_empty() {
+                text = ["aria-label", "title"]
+                    .into_iter()
+                    .find_map(|attr| el.value().attr(attr))
+                    .unwrap_or_default()
+                    .to_string();
+            }
+            let rel = el
+                .value()
+                .attr("rel")
+                .map(|rel| {
+                    rel.split_ascii_whitespace()
+                        .map(str::to_ascii_lowercase)
+                        .collect()
+                })
+                .unwrap_or_default();
+            // mailto:, tel: and the like have no host to compare; they are not
+            // links to another site.
+            let external = matches!(url.scheme(), "http" | "https")
+                && url.host_str().map(str::to_ascii_lowercase) != page_host;
+            Some(PageLink {
+                url: url.to_string(),
+                text: text.split_whitespace().collect::<Vec<_>>().join(" "),
+                rel,
+                external,
+            })
+        })
+        .collect()
+}
+
+/// Narrows a link list by host (including subdomains) and/or a URL regex.
+#[derive(Debug, Default)]
+pub struct LinkFilter {
+    host: Option<String>,
+    pattern: Option<Regex>,
+}
+
+impl LinkFilter {
+    pub fn new(host: Option<&str>, pattern: Option<&str>) -> Result<Self> {
+        let host = host
+            .map(|h| h.trim().trim_start_matches('.').to_ascii_lowercase())
+            .filter(|h| !h.is_empty());
+        let pattern = pattern
+            .filter(|p| !p.trim().is_empty())
+            .map(|p| Regex::new(p).with_context(|| format!("invalid link pattern '{p}'")))
+            .transpose()?;
+        Ok(Self { host, pattern })
+    }
+
+    pub fn matches(&self, link: &PageLink) -> bool {
+        if let Some(want) = &self.host {
+            let host = Url::parse(&link.url)
+                .ok()
+                .and_then(|u| u.host_str().map(str::to_ascii_lowercase));
+            let Some(host) = host else {
+                return false;
+            };
+            if host != *want && !host.ends_with(&format!(".{want}")) {
+                return false;
+            }
+        }
+        self.pattern
+            .as_ref()
+            .is_none_or(|pattern| pattern.is_match(&link.url))
+    }
+}
+
+/// One Markdown list item per link, flagging external and `rel`-qualified ones.
+pub fn render_links(links: &[PageLink]) -> String {
+    let mut out = String::new();
+    for link in links {
+        let text = if link.text.is_empty() {
+            link.url.as_str()
+        } else {
+            link.text.as_str()
+        };
+        out.push_str(&format!("- [{}]({})", text.replace(']', "\\]"), link.url));
+        if link.external {
+            out.push_str(" (external)");
+        }
+        if !link.rel.is_empty() {
+            out.push_str(&format!(" rel={}", link.rel.join(" ")));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<html><body>
+        <a href="/docs/intro">Intro</a>
+        <a href="guide.html#setup">  Setup
+            guide </a>
+        <a href="https://github.com/example/repo" rel="noopener NOFOLLOW">Source</a>
+        <a href="https://blog.example.com/post"><img alt=""></a>
+        <a href="mailto:team@example.org">Mail us</a>
+        <a href="tel:+15550100">Call</a>
+        <a href="javascript:void(0)">Menu</a>
+        <a name="anchor-only">No href</a>
+    </body></html>"#;
+
+    #[test]
+    fn resolves_and_classifies_links() {
+        let page = Url::parse("https://example.com/docs/index.html").unwrap();
+        let links = extract_links(PAGE, &page);
+        let urls: Vec<_> = links.iter().map(|l| l.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://example.com/docs/intro",
+                "https://example.com/docs/guide.html#setup",
+                "https://github.com/example/repo",
+                "https://blog.example.com/post",
+                "mailto:team@example.org",
+                "tel:+15550100",
+            ]
+        );
+        assert_eq!(links[1].text, "Setup guide");
+        assert!(!links[0].external);
+        assert!(links[2].external);
+        assert_eq!(links[2].rel, ["noopener", "nofollow"]);
+        assert!(links[3].external);
+        assert!(!links[4].external);
+        assert!(!links[5].external);
+
+        let md = render_links(&links);
+        assert!(md.contains(
+            "- [Source](https://github.com/example/repo) (external) rel=noopener nofollow\n"
+        ));
+        assert!(md.contains("- [https://blog.example.com/post](https://blog.example.com/post)"));
+    }
+
+    #[test]
+    fn honors_base_href() {
+        let html = r#"<head><base href="https://cdn.example.net/v2/"></head><a href="api">API</a>"#;
+        let links = extract_links(html, &Url::parse("https://example.com/").unwrap());
+        assert_eq!(links[0].url, "https://cdn.example.net/v2/api");
+        assert!(links[0].external);
+    }
+
+    #[test]
+    fn filters_by_host_and_pattern() {
+        let links = extract_links(PAGE, &Url::parse("https://example.com/docs/").unwrap());
+        let by_host = LinkFilter::new(Some("example.com"), None).unwrap();
+        let kept: Vec<_> = links.iter().filter(|l| by_host.matches(l)).collect();
+        assert_eq!(kept.len(), 3);
+
+        let by_pattern = LinkFilter::new(None, Some(r"/docs/")).unwrap();
+        assert_eq!(links.iter().filter(|l| by_pattern.matches(l)).count(), 2);
+
+        assert!(LinkFilter::new(None, Some("(")).is_err());
+    }
+}
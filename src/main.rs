@@ -1,10 +1,13 @@
 use std::sync::Arc;
-use std::{collections::HashSet, fmt};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
 
 use clap::{ArgAction, Parser};
 use rmcp::{
     ErrorData as McpError,
-    model::{CallToolResult, Content},
+    model::{CallToolResult, Content, JsonObject, Meta},
     service::{RequestContext, RoleServer},
 };
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
@@ -20,9 +23,26 @@ use rmcp::{
 };
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Upper bound on the search hits one research call asks for.
+const RESEARCH_MAX_RESULTS: usize = 50;
 
 mod browse;
+mod cache;
+mod charset;
+mod disk_cache;
+mod extract;
+mod host_pattern;
+mod ip_policy;
+mod limits;
+mod links;
+mod metadata;
+mod pdf;
+mod resolver;
+mod retry;
+mod robots;
 mod searxng;
+#[cfg(any(feature = "obscura-backend", test))]
+mod ssrf_proxy;
 
 #[derive(Clone, Debug, PartialEq)]
 enum Transport {
@@ -76,6 +96,12 @@ struct Args {
     )]
     tools: Option<String>,
 
+    #[arg(
+        long,
+        help = "Delete the on-disk browse cache (BROWSE_DISK_CACHE_DIR) and exit"
+    )]
+    clear_browse_cache: bool,
+
     #[arg(
         short = 'v',
         long,
@@ -116,6 +142,16 @@ pub struct SearchRequest {
 
     #[schemars(description = "Override max number of results")]
     pub num_results: Option<usize>,
+
+    #[schemars(
+        description = "Fetch and merge successive pages until num_results is reached (capped by SEARXNG_MAX_PAGES)"
+    )]
+    pub aggregate: Option<bool>,
+
+    #[schemars(
+        description = "Include answers, infoboxes, corrections, unresponsive engines and result count (default: true)"
+    )]
+    pub include_extras: Option<bool>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
@@ -123,8 +159,90 @@ pub struct BrowseRequest {
     #[schemars(description = "The URL to browse")]
     pub url: String,
 
-    #[schemars(description = "Output format: markdown or text")]
+    #[schemars(
+        description = "Output format: markdown, text, or links (every anchor as an absolute URL with its text, rel and whether it is external)"
+    )]
+    pub format: Option<browse::BrowseFormat>,
+
+    #[schemars(description = "With format=links, keep only links to this host or its subdomains")]
+    pub link_host: Option<String>,
+
+    #[schemars(description = "With format=links, keep only links whose URL matches this regex")]
+    pub link_pattern: Option<String>,
+
+    #[schemars(
+        description = "Content to keep: full (whole page) or article (main content only, dropping navigation, sidebars and footers)"
+    )]
+    pub extract: Option<browse::BrowseExtract>,
+
+    #[schemars(
+        description = "CSS selector (e.g. main, table.prices, #api-reference); only the matching elements are rendered"
+    )]
+    pub selector: Option<String>,
+
+    #[schemars(
+        description = "Character offset to start from; use the next_offset of a previous call to continue a long page"
+    )]
+    pub offset: Option<usize>,
+
+    #[schemars(description = "Maximum characters to return in this call")]
+    pub max_chars: Option<usize>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct BrowseManyRequest {
+    #[schemars(description = "The URLs to browse (at most BROWSE_MANY_MAX_URLS)")]
+    pub urls: Vec<String>,
+
+    #[schemars(description = "Output format for every page: markdown, text, or links")]
     pub format: Option<browse::BrowseFormat>,
+
+    #[schemars(description = "Content to keep: full (whole page) or article (main content only)")]
+    pub extract: Option<browse::BrowseExtract>,
+
+    #[schemars(description = "CSS selector applied to every page")]
+    pub selector: Option<String>,
+
+    #[schemars(
+        description = "Maximum characters per page; use browse with next_offset to read further"
+    )]
+    pub max_chars: Option<usize>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct ResearchRequest {
+    #[schemars(description = "The search query")]
+    pub query: String,
+
+    #[schemars(description = "Comma-separated categories")]
+    pub categories: Option<String>,
+
+    #[schemars(description = "Comma-separated engines")]
+    pub engines: Option<String>,
+
+    #[schemars(description = "Language code")]
+    pub language: Option<String>,
+
+    #[schemars(description = "Time range (searxng time_range parameter)")]
+    pub time_range: Option<String>,
+
+    #[schemars(description = "Safe search level")]
+    pub safe_search: Option<searxng::SafeSearch>,
+
+    #[schemars(
+        description = "How many top results to fetch (default: BROWSE_RESEARCH_PAGES, capped by BROWSE_MANY_MAX_URLS)"
+    )]
+    pub num_pages: Option<usize>,
+
+    #[schemars(
+        description = "Maximum excerpt characters per page (default: BROWSE_RESEARCH_MAX_CHARS)"
+    )]
+    pub max_chars: Option<usize>,
+
+    #[schemars(
+        description = "Content to excerpt: article (main content only, the default) or full (whole page)"
+    )]
+    pub extract: Option<browse::BrowseExtract>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
@@ -136,6 +254,15 @@ pub struct BrowseEvalRequest {
     pub script: String,
 }
 
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct AutocompleteRequest {
+    #[schemars(description = "Partial query to complete")]
+    pub query: String,
+
+    #[schemars(description = "Language code")]
+    pub language: Option<String>,
+}
+
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 pub struct EnginesRequest {
     #[schemars(description = "Which engines to return")]
@@ -148,11 +275,166 @@ pub struct HealthRequest {
     pub include_engines: Option<bool>,
 }
 
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+pub struct BrowseResponse {
+    #[schemars(description = "The URL that was requested")]
+    pub url: String,
+
+    #[schemars(description = "Format of content")]
+    pub format: browse::BrowseFormat,
+
+    #[schemars(description = "Whether the whole page or only its main content was kept")]
+    pub extract: browse::BrowseExtract,
+
+    #[schemars(description = "Rendered page content, starting at offset")]
+    pub content: String,
+
+    #[schemars(description = "Character offset of content within the rendered page")]
+    pub offset: usize,
+
+    #[schemars(description = "Length of the whole rendered page in characters")]
+    pub total_chars: usize,
+
+    #[schemars(
+        description = "Offset to pass to get the next chunk, absent at the end of the page"
+    )]
+    pub next_offset: Option<usize>,
+
+    #[schemars(
+        description = "Title, description, author, dates, canonical URL, language, OpenGraph/Twitter cards and JSON-LD of the page"
+    )]
+    pub metadata: metadata::PageMetadata,
+
+    #[schemars(description = "Links on the page, for format=links")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub links: Option<Vec<links::PageLink>>,
+}
+
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+pub struct BrowseManyResult {
+    #[schemars(description = "The URL as requested")]
+    pub url: String,
+
+    #[schemars(description = "The start of the rendered page, absent on error")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+
+    #[schemars(description = "Length of the whole rendered page in characters")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_chars: Option<usize>,
+
+    #[schemars(description = "Offset to pass to browse for the rest of the page")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_offset: Option<usize>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<metadata::PageMetadata>,
+
+    #[schemars(description = "Why this URL could not be browsed")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+pub struct BrowseManyResponse {
+    pub format: browse::BrowseFormat,
+    pub extract: browse::BrowseExtract,
+
+    #[schemars(description = "One entry per requested URL, in request order")]
+    pub results: Vec<BrowseManyResult>,
+}
+
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+pub struct ResearchResult {
+    #[schemars(description = "Rank among the search results, starting at 1")]
+    pub rank: usize,
+    pub title: String,
+    pub url: String,
+
+    #[schemars(description = "The search engine's snippet")]
+    pub snippet: String,
+    pub engines: Vec<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published_date: Option<String>,
+
+    #[schemars(description = "The start of the page's rendered content")]
+    pub excerpt: String,
+
+    #[schemars(description = "Length of the whole rendered page in characters")]
+    pub total_chars: usize,
+
+    #[schemars(
+        description = "Offset to pass to browse, with the response's format and extract, for the rest of the page"
+    )]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_offset: Option<usize>,
+    pub metadata: metadata::PageMetadata,
+}
+
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+pub struct ResearchSkipped {
+    pub rank: usize,
+    pub url: String,
+    #[schemars(
+        description = "Why the page was left out (SSRF policy, robots.txt, fetch error, ...)"
+    )]
+    pub error: String,
+}
+
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+pub struct ResearchResponse {
+    pub query: String,
+
+    #[schemars(description = "Format of the excerpts; pass it to browse to continue a page")]
+    pub format: browse::BrowseFormat,
+
+    #[schemars(description = "Extract mode of the excerpts; pass it to browse to continue a page")]
+    pub extract: browse::BrowseExtract,
+
+    #[schemars(description = "Fetched pages, in search rank order")]
+    pub results: Vec<ResearchResult>,
+
+    #[schemars(description = "Top results that could not be fetched")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub skipped: Vec<ResearchSkipped>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub answers: Vec<searxng::Answer>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub suggestions: Vec<String>,
+}
+
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+pub struct AutocompleteResponse {
+    pub query: String,
+
+    #[schemars(description = "Suggested completions, best first")]
+    pub suggestions: Vec<String>,
+}
+
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+pub struct HealthResponse {
+    pub ok: bool,
+    pub version: String,
+    #[schemars(description = "Number of enabled engines, if requested")]
+    pub engines_enabled: Option<usize>,
+    #[schemars(description = "Multi-instance strategy: failover, roundrobin or fanout")]
+    pub strategy: String,
+    #[schemars(description = "Circuit breaker state: closed, open or half_open")]
+    pub circuit: String,
+    pub instances: Vec<searxng::InstanceHealth>,
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
 enum ToolName {
     Search,
     Browse,
+    BrowseMany,
+    Research,
     BrowseEval,
+    Autocomplete,
     Engines,
     Health,
     Ping,
@@ -163,7 +445,10 @@ impl ToolName {
         match self {
             ToolName::Search => "search",
             ToolName::Browse => "browse",
+            ToolName::BrowseMany => "browse_many",
+            ToolName::Research => "research",
             ToolName::BrowseEval => "browse_eval",
+            ToolName::Autocomplete => "autocomplete",
             ToolName::Engines => "engines",
             ToolName::Health => "health",
             ToolName::Ping => "ping",
@@ -174,7 +459,10 @@ impl ToolName {
         match s.trim().to_ascii_lowercase().as_str() {
             "search" => Some(Self::Search),
             "browse" => Some(Self::Browse),
+            "browse_many" => Some(Self::BrowseMany),
+            "research" => Some(Self::Research),
             "browse_eval" => Some(Self::BrowseEval),
+            "autocomplete" => Some(Self::Autocomplete),
             "engines" => Some(Self::Engines),
             "health" => Some(Self::Health),
             "ping" => Some(Self::Ping),
@@ -211,7 +499,7 @@ fn parse_enabled_tools(s: &str) -> anyhow::Result<HashSet<ToolName>> {
 
     if !unknown.is_empty() {
         return Err(anyhow::anyhow!(
-            "unknown tools: {} (valid: search,browse,browse_eval,engines,health,ping)",
+            "unknown tools: {} (valid: search,browse,browse_many,research,browse_eval,autocomplete,engines,health,ping)",
             unknown.join(",")
         ));
     }
@@ -219,12 +507,48 @@ fn parse_enabled_tools(s: &str) -> anyhow::Result<HashSet<ToolName>> {
     Ok(out)
 }
 
+#[derive(Debug)]
+struct ResponseCaches {
+    search: cache::TtlCache<String, searxng::SearxngResponse>,
+    browse: cache::TtlCache<browse::BrowseCacheKey, Arc<browse::BrowsePage>>,
+}
+
+impl ResponseCaches {
+    fn new(searxng: &searxng::SearxngConfig, browse: &browse::BrowseConfig) -> Self {
+        Self {
+            search: cache::TtlCache::new(searxng.cache_max_entries, searxng.cache_ttl),
+            browse: cache::TtlCache::new(browse.cache_max_entries, browse.cache_ttl),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SearxngMcpServer {
     #[allow(dead_code)]
     tool_router: ToolRouter<Self>,
     searxng: Arc<searxng::SearxngClient>,
     browse: Arc<browse::BrowseConfig>,
+    caches: Arc<ResponseCaches>,
+}
+
+fn output_schema<T: schemars::JsonSchema + 'static>() -> Arc<JsonObject> {
+    rmcp::handler::server::tool::schema_for_output::<T>()
+        .unwrap_or_else(|e| panic!("invalid tool output schema: {e}"))
+}
+
+fn structured_value<T: serde::Serialize>(value: &T) -> Result<serde_json::Value, McpError> {
+    serde_json::to_value(value)
+        .map_err(|e| McpError::internal_error(format!("serialization failed: {e}"), None))
+}
+
+fn with_cache_meta(mut result: CallToolResult, status: cache::CacheStatus) -> CallToolResult {
+    let mut meta = Meta::new();
+    meta.0.insert(
+        "cache".to_string(),
+        serde_json::Value::from(status.as_str()),
+    );
+    result.meta = Some(meta);
+    result
 }
 
 fn truncate_for_log(s: &str, max: usize) -> String {
@@ -237,18 +561,67 @@ fn truncate_for_log(s: &str, max: usize) -> String {
     out
 }
 
+/// A chunk followed by its continuation hint. `opts` are the options the page
+/// was rendered with, so the hint leads back to the same document.
+fn chunk_text(chunk: &browse::BrowseChunk, opts: &browse::BrowseOptions) -> String {
+    let mut text = chunk.content.clone();
+    if let Some(hint) = chunk.continuation_hint(opts) {
+        text.push_str("\n\n");
+        text.push_str(&hint);
+    }
+    text
+}
+
+/// How many pages research fetches: as requested or configured, but never
+/// more than one browse_many call may.
+fn research_page_count(requested: Option<usize>, cfg: &browse::BrowseConfig) -> usize {
+    requested
+        .unwrap_or(cfg.research_pages)
+        .min(cfg.many_max_urls)
+}
+
+/// Search hits research asks for: twice the pages wanted, so that duplicates,
+/// non-web results and pages that fail to load can be made up for.
+fn research_num_results(num_pages: usize) -> usize {
+    num_pages
+        .saturating_mul(2)
+        .min(RESEARCH_MAX_RESULTS)
+        .max(num_pages)
+}
+
+/// The top `num_pages` results with fetchable URLs, each URL once, paired with
+/// their 1-based rank in the full result list.
+fn research_hits(
+    results: &[searxng::SearchResult],
+    num_pages: usize,
+) -> Vec<(usize, &searxng::SearchResult)> {
+    let mut seen = HashSet::new();
+    results
+        .iter()
+        .enumerate()
+        .filter(|(_, r)| r.url.starts_with("http://") || r.url.starts_with("https://"))
+        .filter(|(_, r)| seen.insert(r.url.as_str()))
+        .take(num_pages)
+        .map(|(i, r)| (i + 1, r))
+        .collect()
+}
+
 #[tool_router]
 impl SearxngMcpServer {
     fn new(
         searxng: Arc<searxng::SearxngClient>,
         browse: Arc<browse::BrowseConfig>,
+        caches: Arc<ResponseCaches>,
         enabled: HashSet<ToolName>,
     ) -> Self {
         let mut tool_router = Self::tool_router();
         for tool in [
             ToolName::Search,
             ToolName::Browse,
+            ToolName::BrowseMany,
+            ToolName::Research,
             ToolName::BrowseEval,
+            ToolName::Autocomplete,
             ToolName::Engines,
             ToolName::Health,
             ToolName::Ping,
@@ -262,7 +635,68 @@ impl SearxngMcpServer {
             tool_router,
             searxng,
             browse,
+            caches,
+        }
+    }
+
+    /// Run a search, from the in-memory cache when possible.
+    async fn run_search(
+        &self,
+        params: searxng::SearchParams,
+    ) -> anyhow::Result<(searxng::SearxngResponse, cache::CacheStatus)> {
+        let cache_key = self.searxng.cache_key(&params)?;
+        let (cached, cache_status) = self.caches.search.lookup(&cache_key);
+        let resp = match cached {
+            Some(resp) => resp,
+            None => {
+                let resp = self.searxng.search(params).await?;
+                self.caches.search.insert(cache_key, resp.clone());
+                resp
+            }
+        };
+        Ok((resp, cache_status))
+    }
+
+    /// Render `url`, from the in-memory cache when possible.
+    async fn load_page(
+        &self,
+        url: &str,
+        opts: &browse::BrowseOptions,
+    ) -> anyhow::Result<(
+        browse::BrowseCacheKey,
+        Arc<browse::BrowsePage>,
+        cache::CacheStatus,
+    )> {
+        let cache_key = browse::BrowseCacheKey::new(url, opts, self.browse.as_ref());
+        let (cached, mut cache_status) = self.caches.browse.lookup(&cache_key);
+        let page = match cached {
+            Some(page) => page,
+            None => {
+                let page = browse::browse_with_config(url, opts, self.browse.as_ref()).await?;
+                if page.from_disk_cache {
+                    cache_status = cache::CacheStatus::Revalidated;
+                }
+                let page = Arc::new(page);
+                self.caches.browse.insert(cache_key.clone(), page.clone());
+                page
+            }
+        };
+        Ok((cache_key, page, cache_status))
+    }
+
+    /// The first `max_chars` characters of `url`'s rendered page, for the batch tools.
+    async fn load_excerpt(
+        &self,
+        url: &str,
+        opts: &browse::BrowseOptions,
+        max_chars: usize,
+    ) -> anyhow::Result<(browse::BrowseChunk, Arc<browse::BrowsePage>)> {
+        if url.is_empty() {
+            return Err(anyhow::anyhow!("url must be non-empty"));
         }
+        let (_, page, _) = self.load_page(url, opts).await?;
+        let chunk = browse::chunk_document(&page.content, 0, max_chars)?;
+        Ok((chunk, page))
     }
 
     #[tool(description = "Health check")]
@@ -275,7 +709,10 @@ impl SearxngMcpServer {
         Ok(CallToolResult::success(vec![Content::text(msg)]))
     }
 
-    #[tool(description = "Perform web search using SearXNG")]
+    #[tool(
+        description = "Perform web search using SearXNG",
+        output_schema = output_schema::<searxng::SearxngResponse>()
+    )]
     async fn search(
         &self,
         _context: RequestContext<RoleServer>,
@@ -297,6 +734,7 @@ impl SearxngMcpServer {
         );
 
         let started = std::time::Instant::now();
+        let include_extras = req.include_extras.unwrap_or(true);
         let params = searxng::SearchParams {
             query: req.query,
             categories: req.categories,
@@ -306,32 +744,64 @@ impl SearxngMcpServer {
             time_range: req.time_range,
             safe_search: req.safe_search,
             num_results: req.num_results,
+            aggregate: req.aggregate,
         };
 
-        let resp = self
-            .searxng
-            .search(params)
+        let (mut resp, cache_status) = self
+            .run_search(params)
             .await
             .map_err(|e| McpError::internal_error(format!("search failed: {e}"), None))?;
 
+        if !resp.unresponsive_engines.is_empty() {
+            tracing::warn!(
+                engines = %resp
+                    .unresponsive_engines
+                    .iter()
+                    .map(|e| e.engine.as_str())
+                    .collect::<Vec<_>>()
+                    .join(","),
+                "searxng reported unresponsive engines"
+            );
+        }
+
         tracing::info!(
             elapsed_ms = started.elapsed().as_millis(),
             results = resp.results.len(),
             suggestions = resp.suggestions.len(),
+            answers = resp.answers.len(),
+            infoboxes = resp.infoboxes.len(),
+            unresponsive_engines = resp.unresponsive_engines.len(),
+            cache = cache_status.as_str(),
             "mcp.search response"
         );
 
-        let json = serde_json::to_string(&resp)
-            .unwrap_or_else(|_| "{\"error\":\"serialization failed\"}".to_string());
+        if !include_extras {
+            resp.strip_extras();
+        }
 
-        Ok(CallToolResult::success(vec![Content::text(json)]))
+        Ok(with_cache_meta(
+            CallToolResult::structured(structured_value(&resp)?),
+            cache_status,
+        ))
     }
 
-    #[tool(description = "Fetch content from a URL as Markdown")]
+    #[tool(
+        description = "Fetch content from a URL as Markdown",
+        output_schema = output_schema::<BrowseResponse>()
+    )]
     async fn browse(
         &self,
         _context: RequestContext<RoleServer>,
-        Parameters(BrowseRequest { url, format }): Parameters<BrowseRequest>,
+        Parameters(BrowseRequest {
+            url,
+            format,
+            link_host,
+            link_pattern,
+            extract,
+            selector,
+            offset,
+            max_chars,
+        }): Parameters<BrowseRequest>,
     ) -> Result<CallToolResult, McpError> {
         if url.trim().is_empty() {
             return Err(McpError::internal_error(
@@ -340,20 +810,326 @@ impl SearxngMcpServer {
             ));
         }
 
+        let link_filter = links::LinkFilter::new(link_host.as_deref(), link_pattern.as_deref())
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+
         tracing::info!(url = %truncate_for_log(&url, 200), "mcp.browse request");
         let started = std::time::Instant::now();
 
-        let md = crate::browse::browse_with_config(&url, format, self.browse.as_ref())
+        let opts = browse::BrowseOptions {
+            format,
+            extract,
+            selector: selector.filter(|s| !s.trim().is_empty()),
+        };
+        let (cache_key, page, cache_status) = self
+            .load_page(&url, &opts)
             .await
             .map_err(|e| McpError::internal_error(format!("browse failed: {e}"), None))?;
 
+        let (content, links) = if cache_key.format == browse::BrowseFormat::Links {
+            let kept: Vec<links::PageLink> = page
+                .links
+                .iter()
+                .filter(|link| link_filter.matches(link))
+                .cloned()
+                .collect();
+            (
+                std::borrow::Cow::Owned(links::render_links(&kept)),
+                Some(kept),
+            )
+        } else {
+            (std::borrow::Cow::Borrowed(page.content.as_str()), None)
+        };
+
+        let chunk = browse::chunk_document(
+            &content,
+            offset.unwrap_or(0),
+            max_chars.unwrap_or(self.browse.max_chars),
+        )
+        .map_err(|e| McpError::internal_error(format!("browse failed: {e}"), None))?;
+
         tracing::info!(
             elapsed_ms = started.elapsed().as_millis(),
-            md_len = md.len(),
+            md_len = page.content.len(),
+            offset = chunk.offset,
+            next_offset = chunk.next_offset,
+            cache = cache_status.as_str(),
             "mcp.browse response"
         );
 
-        Ok(CallToolResult::success(vec![Content::text(md)]))
+        let text = chunk_text(&chunk, &opts);
+        let structured = structured_value(&BrowseResponse {
+            url,
+            format: cache_key.format,
+            extract: cache_key.extract,
+            content: chunk.content,
+            offset: chunk.offset,
+            total_chars: chunk.total_chars,
+            next_offset: chunk.next_offset,
+            metadata: page.metadata.clone(),
+            links,
+        })?;
+        // Keep the plain rendered page as the text block; it is what agents read.
+        let mut result = CallToolResult::success(vec![Content::text(text)]);
+        result.structured_content = Some(structured);
+        Ok(with_cache_meta(result, cache_status))
+    }
+
+    #[tool(
+        description = "Fetch several URLs concurrently; returns each page's content or error, in request order",
+        output_schema = output_schema::<BrowseManyResponse>()
+    )]
+    async fn browse_many(
+        &self,
+        _context: RequestContext<RoleServer>,
+        Parameters(BrowseManyRequest {
+            urls,
+            format,
+            extract,
+            selector,
+            max_chars,
+        }): Parameters<BrowseManyRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        if urls.is_empty() {
+            return Err(McpError::internal_error(
+                "urls must be non-empty".to_string(),
+                None,
+            ));
+        }
+        if urls.len() > self.browse.many_max_urls {
+            return Err(McpError::internal_error(
+                format!(
+                    "at most {} urls per call (BROWSE_MANY_MAX_URLS), got {}",
+                    self.browse.many_max_urls,
+                    urls.len()
+                ),
+                None,
+            ));
+        }
+
+        tracing::info!(count = urls.len(), "mcp.browse_many request");
+        let started = std::time::Instant::now();
+
+        let opts = browse::BrowseOptions {
+            format,
+            extract,
+            selector: selector.filter(|s| !s.trim().is_empty()),
+        };
+        let max_chars = max_chars.unwrap_or(self.browse.max_chars);
+        let outcomes = browse::browse_batch(
+            &urls,
+            self.browse.many_concurrency,
+            self.browse.many_deadline,
+            |url| self.load_excerpt(url, &opts, max_chars),
+        )
+        .await;
+
+        let mut text = String::new();
+        let mut results = Vec::with_capacity(urls.len());
+        for (url, outcome) in urls.into_iter().zip(outcomes) {
+            if !text.is_empty() {
+                text.push_str("\n\n---\n\n");
+            }
+            text.push_str(&format!("# {url}\n\n"));
+            match outcome {
+                Ok((chunk, page)) => {
+                    text.push_str(&chunk_text(&chunk, &opts));
+                    results.push(BrowseManyResult {
+                        url,
+                        content: Some(chunk.content),
+                        total_chars: Some(chunk.total_chars),
+                        next_offset: chunk.next_offset,
+                        metadata: Some(page.metadata.clone()),
+                        error: None,
+                    });
+                }
+                Err(e) => {
+                    let error = format!("browse failed: {e}");
+                    text.push_str(&error);
+                    results.push(BrowseManyResult {
+                        url,
+                        content: None,
+                        total_chars: None,
+                        next_offset: None,
+                        metadata: None,
+                        error: Some(error),
+                    });
+                }
+            }
+        }
+
+        tracing::info!(
+            elapsed_ms = started.elapsed().as_millis(),
+            failed = results.iter().filter(|r| r.error.is_some()).count(),
+            "mcp.browse_many response"
+        );
+
+        let structured = structured_value(&BrowseManyResponse {
+            format: opts.format.unwrap_or(self.browse.format),
+            extract: opts.extract.unwrap_or(self.browse.extract),
+            results,
+        })?;
+        let mut result = CallToolResult::success(vec![Content::text(text)]);
+        result.structured_content = Some(structured);
+        Ok(result)
+    }
+
+    #[tool(
+        description = "Search, then fetch the top results in parallel and return an excerpt of each page with its metadata",
+        output_schema = output_schema::<ResearchResponse>()
+    )]
+    async fn research(
+        &self,
+        _context: RequestContext<RoleServer>,
+        Parameters(req): Parameters<ResearchRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        if req.query.trim().is_empty() {
+            return Err(McpError::internal_error(
+                "query must be non-empty".to_string(),
+                None,
+            ));
+        }
+        let max_chars = req.max_chars.unwrap_or(self.browse.research_max_chars);
+        if max_chars == 0 {
+            return Err(McpError::internal_error(
+                "max_chars must be greater than 0".to_string(),
+                None,
+            ));
+        }
+        let num_pages = research_page_count(req.num_pages, &self.browse);
+
+        tracing::info!(
+            query = %truncate_for_log(&req.query, 120),
+            num_pages,
+            "mcp.research request"
+        );
+        let started = std::time::Instant::now();
+
+        let params = searxng::SearchParams {
+            query: req.query.clone(),
+            categories: req.categories,
+            engines: req.engines,
+            language: req.language,
+            time_range: req.time_range,
+            safe_search: req.safe_search,
+            num_results: Some(research_num_results(num_pages)),
+            aggregate: Some(true),
+            ..Default::default()
+        };
+        let (resp, _) = self
+            .run_search(params)
+            .await
+            .map_err(|e| McpError::internal_error(format!("search failed: {e}"), None))?;
+
+        let mut candidates = research_hits(&resp.results, usize::MAX).into_iter();
+
+        // Excerpts read best as prose, so a links default does not apply here.
+        let format = match self.browse.format {
+            browse::BrowseFormat::Links => browse::BrowseFormat::Markdown,
+            format => format,
+        };
+        let extract = req.extract.unwrap_or(browse::BrowseExtract::Article);
+        let opts = browse::BrowseOptions {
+            format: Some(format),
+            extract: Some(extract),
+            selector: None,
+        };
+
+        let mut results = Vec::new();
+        let mut hints = Vec::new();
+        let mut skipped = Vec::new();
+        // Pages that fail to load are replaced by the next hits, within the
+        // overall deadline.
+        while results.len() < num_pages {
+            let remaining = self.browse.many_deadline.saturating_sub(started.elapsed());
+            if remaining.is_zero() {
+                break;
+            }
+            let batch: Vec<(usize, &searxng::SearchResult)> = candidates
+                .by_ref()
+                .take(num_pages - results.len())
+                .collect();
+            if batch.is_empty() {
+                break;
+            }
+            let urls: Vec<String> = batch.iter().map(|(_, r)| r.url.clone()).collect();
+            let outcomes =
+                browse::browse_batch(&urls, self.browse.many_concurrency, remaining, |url| {
+                    self.load_excerpt(url, &opts, max_chars)
+                })
+                .await;
+
+            for ((rank, hit), outcome) in batch.into_iter().zip(outcomes) {
+                match outcome {
+                    Ok((chunk, page)) => {
+                        hints.push(chunk.continuation_hint(&opts));
+                        results.push(ResearchResult {
+                            rank,
+                            title: hit.title.clone(),
+                            url: hit.url.clone(),
+                            snippet: hit.content.clone(),
+                            engines: hit.engines.clone(),
+                            published_date: hit.published_date.clone(),
+                            excerpt: chunk.content,
+                            total_chars: chunk.total_chars,
+                            next_offset: chunk.next_offset,
+                            metadata: page.metadata.clone(),
+                        });
+                    }
+                    Err(e) => {
+                        tracing::debug!(url = %hit.url, error = %e, "research skipped result");
+                        skipped.push(ResearchSkipped {
+                            rank,
+                            url: hit.url.clone(),
+                            error: e.to_string(),
+                        });
+                    }
+                }
+            }
+        }
+
+        tracing::info!(
+            elapsed_ms = started.elapsed().as_millis(),
+            results = results.len(),
+            skipped = skipped.len(),
+            "mcp.research response"
+        );
+
+        let mut text = format!("# Research: {}\n", req.query.trim());
+        for answer in &resp.answers {
+            text.push_str(&format!("\nAnswer: {}\n", answer.answer));
+        }
+        for (r, hint) in results.iter().zip(&hints) {
+            text.push_str(&format!(
+                "\n## {}. {}\n{}\n\n{}\n",
+                r.rank, r.title, r.url, r.excerpt
+            ));
+            if let Some(hint) = hint {
+                text.push_str(&format!("\n{hint}\n"));
+            }
+        }
+        if results.is_empty() {
+            text.push_str("\nNo results could be fetched.\n");
+        }
+        if !skipped.is_empty() {
+            text.push_str("\nSkipped:\n");
+            for s in &skipped {
+                text.push_str(&format!("- {}: {}\n", s.url, s.error));
+            }
+        }
+
+        let structured = structured_value(&ResearchResponse {
+            query: req.query,
+            format,
+            extract,
+            results,
+            skipped,
+            answers: resp.answers,
+            suggestions: resp.suggestions,
+        })?;
+        let mut result = CallToolResult::success(vec![Content::text(text)]);
+        result.structured_content = Some(structured);
+        Ok(result)
     }
 
     #[tool(description = "Evaluate JavaScript on a loaded page using the Obscura browse backend")]
@@ -397,7 +1173,46 @@ impl SearxngMcpServer {
         Ok(CallToolResult::success(vec![Content::text(result)]))
     }
 
-    #[tool(description = "List configured SearXNG engines")]
+    #[tool(
+        description = "Suggest query completions using SearXNG's autocompleter",
+        output_schema = output_schema::<AutocompleteResponse>()
+    )]
+    async fn autocomplete(
+        &self,
+        _context: RequestContext<RoleServer>,
+        Parameters(AutocompleteRequest { query, language }): Parameters<AutocompleteRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        if query.trim().is_empty() {
+            return Err(McpError::internal_error(
+                "query must be non-empty".to_string(),
+                None,
+            ));
+        }
+
+        tracing::info!(query = %truncate_for_log(&query, 120), "mcp.autocomplete request");
+        let started = std::time::Instant::now();
+
+        let suggestions = self
+            .searxng
+            .autocomplete(&query, language.as_deref())
+            .await
+            .map_err(|e| McpError::internal_error(format!("autocomplete failed: {e}"), None))?;
+
+        tracing::info!(
+            elapsed_ms = started.elapsed().as_millis(),
+            suggestions = suggestions.len(),
+            "mcp.autocomplete response"
+        );
+
+        Ok(CallToolResult::structured(structured_value(
+            &AutocompleteResponse { query, suggestions },
+        )?))
+    }
+
+    #[tool(
+        description = "List configured SearXNG engines",
+        output_schema = output_schema::<HashMap<String, serde_json::Value>>()
+    )]
     async fn engines(
         &self,
         _context: RequestContext<RoleServer>,
@@ -420,13 +1235,13 @@ impl SearxngMcpServer {
             "mcp.engines response"
         );
 
-        let json = serde_json::to_string(&engines)
-            .unwrap_or_else(|_| "{\"error\":\"serialization failed\"}".to_string());
-
-        Ok(CallToolResult::success(vec![Content::text(json)]))
+        Ok(CallToolResult::structured(structured_value(&engines)?))
     }
 
-    #[tool(description = "Check connectivity to configured SearXNG instance")]
+    #[tool(
+        description = "Check connectivity to each configured SearXNG instance",
+        output_schema = output_schema::<HealthResponse>()
+    )]
     async fn health(
         &self,
         _context: RequestContext<RoleServer>,
@@ -434,13 +1249,11 @@ impl SearxngMcpServer {
     ) -> Result<CallToolResult, McpError> {
         let started = std::time::Instant::now();
 
-        self.searxng
-            .test_connection()
-            .await
-            .map_err(|e| McpError::internal_error(format!("health failed: {e}"), None))?;
+        let instances = self.searxng.instance_health().await;
+        let ok = instances.iter().any(|i| i.ok);
 
         let mut engines_count: Option<usize> = None;
-        if include_engines.unwrap_or(false) {
+        if ok && include_engines.unwrap_or(false) {
             let engines = self
                 .searxng
                 .get_engines(searxng::EngineFilter::Enabled)
@@ -453,17 +1266,24 @@ impl SearxngMcpServer {
             elapsed_ms = started.elapsed().as_millis(),
             include_engines = include_engines.unwrap_or(false),
             engines_count = engines_count.unwrap_or(0),
+            instances = instances.len(),
+            instances_ok = instances.iter().filter(|i| i.ok).count(),
             "mcp.health response"
         );
 
-        let payload = serde_json::json!({
-            "ok": true,
-            "version": VERSION,
-            "engines_enabled": engines_count,
-        });
-        Ok(CallToolResult::success(vec![Content::text(
-            payload.to_string(),
-        )]))
+        let payload = structured_value(&HealthResponse {
+            ok,
+            version: VERSION.to_string(),
+            engines_enabled: engines_count,
+            strategy: self.searxng.config().strategy.as_str().to_string(),
+            circuit: self.searxng.circuit_state().as_str().to_string(),
+            instances,
+        })?;
+        if ok {
+            Ok(CallToolResult::structured(payload))
+        } else {
+            Ok(CallToolResult::structured_error(payload))
+        }
     }
 }
 
@@ -479,10 +1299,27 @@ impl ServerHandler for SearxngMcpServer {
 async fn main() -> anyhow::Result<()> {
     let args = Args::parse();
 
-    let searxng_cfg = searxng::SearxngConfig::from_env();
+    let searxng_cfg = searxng::SearxngConfig::from_env()?;
     let searxng_client = Arc::new(searxng::SearxngClient::new(searxng_cfg)?);
 
     let browse_cfg = Arc::new(browse::BrowseConfig::from_env()?);
+    if args.clear_browse_cache {
+        let Some(disk) = browse_cfg.disk_cache.as_ref() else {
+            return Err(anyhow::anyhow!(
+                "--clear-browse-cache requires BROWSE_DISK_CACHE_DIR"
+            ));
+        };
+        let removed = disk.clear()?;
+        eprintln!(
+            "removed {removed} cached responses from {}",
+            disk.dir().display()
+        );
+        return Ok(());
+    }
+    let caches = Arc::new(ResponseCaches::new(
+        searxng_client.config(),
+        browse_cfg.as_ref(),
+    ));
 
     let log_filter = if std::env::var_os("RUST_LOG").is_some() {
         tracing_subscriber::EnvFilter::try_from_default_env()
@@ -561,7 +1398,12 @@ async fn main() -> anyhow::Result<()> {
         Transport::Stdio => {
             let enabled = enabled_tools.clone();
             let service = serve_server(
-                SearxngMcpServer::new(searxng_client.clone(), browse_cfg.clone(), enabled),
+                SearxngMcpServer::new(
+                    searxng_client.clone(),
+                    browse_cfg.clone(),
+                    caches.clone(),
+                    enabled,
+                ),
                 stdio(),
             )
             .await?;
@@ -598,11 +1440,13 @@ async fn main() -> anyhow::Result<()> {
             let searxng_for_service = searxng_client.clone();
             let enabled_for_service = enabled_tools.clone();
             let browse_for_service = browse_cfg.clone();
+            let caches_for_service = caches.clone();
             let service = StreamableHttpService::new(
                 move || {
                     Ok(SearxngMcpServer::new(
                         searxng_for_service.clone(),
                         browse_for_service.clone(),
+                        caches_for_service.clone(),
                         enabled_for_service.clone(),
                     ))
                 },
@@ -621,3 +1465,89 @@ async fn main() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn browse_hint_repeats_the_rendering_options() {
+        let doc = "# Intro\n\nFirst paragraph here.\n\n## Usage\n\nSecond paragraph.\n";
+        let chunk = browse::chunk_document(doc, 0, 40).unwrap();
+        let opts = browse::BrowseOptions {
+            format: Some(browse::BrowseFormat::Text),
+            extract: Some(browse::BrowseExtract::Article),
+            selector: Some("main .docs".to_string()),
+        };
+        let text = chunk_text(&chunk, &opts);
+        assert!(text.starts_with(&chunk.content));
+        assert!(
+            text.ends_with(
+                "Call browse again with format=text, extract=article, selector=\"main .docs\", offset=32 to continue.]"
+            ),
+            "{text}"
+        );
+
+        let whole = browse::chunk_document(doc, 0, 1_000).unwrap();
+        assert_eq!(chunk_text(&whole, &opts), doc);
+    }
+
+    #[test]
+    fn research_picks_top_distinct_http_results() {
+        let results: Vec<searxng::SearchResult> = serde_json::from_str(
+            r#"[
+                {"title": "A", "url": "https://a.example/"},
+                {"title": "Mail", "url": "mailto:someone@example.com"},
+                {"title": "A again", "url": "https://a.example/"},
+                {"title": "B", "url": "http://b.example/page"},
+                {"title": "FTP", "url": "ftp://c.example/file"},
+                {"title": "C", "url": "https://c.example/"},
+                {"title": "D", "url": "https://d.example/"}
+            ]"#,
+        )
+        .unwrap();
+
+        let picked: Vec<(usize, &str)> = research_hits(&results, 3)
+            .into_iter()
+            .map(|(rank, r)| (rank, r.title.as_str()))
+            .collect();
+        assert_eq!(picked, vec![(1, "A"), (4, "B"), (6, "C")]);
+        assert_eq!(research_hits(&results, 10).len(), 4);
+        assert!(research_hits(&results, 0).is_empty());
+
+        let mut cfg = browse::BrowseConfig::default();
+        cfg.research_pages = 5;
+        cfg.many_max_urls = 8;
+        assert_eq!(research_page_count(None, &cfg), 5);
+        assert_eq!(research_page_count(Some(2), &cfg), 2);
+        assert_eq!(research_page_count(Some(20), &cfg), 8);
+    }
+
+    #[test]
+    fn research_searches_past_the_default_result_count() {
+        let default_results = searxng::SearxngConfig::default().num_results;
+        let mut cfg = browse::BrowseConfig::default();
+        cfg.many_max_urls = 20;
+        let num_pages = research_page_count(Some(default_results + 3), &cfg);
+        assert_eq!(num_pages, default_results + 3);
+
+        let wanted = research_num_results(num_pages);
+        assert!(wanted >= num_pages * 2, "{wanted}");
+        assert_eq!(research_num_results(1_000), 1_000);
+        assert_eq!(research_num_results(40), RESEARCH_MAX_RESULTS);
+
+        // Enough spare hits to fill every page despite duplicates and non-web results.
+        let results: Vec<searxng::SearchResult> = (0..wanted)
+            .map(|i| {
+                let url = match i % 4 {
+                    0 => format!("https://example.com/{}", i / 2),
+                    1 => format!("mailto:user{i}@example.com"),
+                    _ => format!("https://example.com/{i}"),
+                };
+                serde_json::from_value(serde_json::json!({"title": format!("r{i}"), "url": url}))
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(research_hits(&results, num_pages).len(), num_pages);
+    }
+}
@@ -0,0 +1,233 @@
+use std::collections::BTreeMap;
+
+use reqwest::Url;
+use scraper::{Html, Selector};
+use serde_json::Value;
+
+/// Citation-relevant facts about a page, gathered from its `<head>`.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, schemars::JsonSchema)]
+pub struct PageMetadata {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modified: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub site_name: Option<String>,
+    #[schemars(description = "Absolute canonical URL of the page")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub canonical_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_count: Option<usize>,
+    #[schemars(description = "OpenGraph properties (og:*, article:*) by property name")]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub open_graph: BTreeMap<String, String>,
+    #[schemars(description = "Twitter card properties (twitter:*) by name")]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub twitter: BTreeMap<String, String>,
+    #[schemars(description = "schema.org JSON-LD blocks, as published")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub json_ld: Vec<Value>,
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!value.is_empty()).then_some(value)
+}
+
+/// schema.org objects in a JSON-LD block, including `@graph` members.
+fn json_ld_objects(value: &Value) -> Vec<&serde_json::Map<String, Value>> {
+    match value {
+        Value::Array(items) => items.iter().flat_map(json_ld_objects).collect(),
+        Value::Object(obj) => {
+            let mut out = vec![obj];
+            if let Some(graph) = obj.get("@graph") {
+                out.extend(json_ld_objects(graph));
+            }
+            out
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// A schema.org `author`/`publisher`: a string, a Person/Organization or a list of them.
+fn json_ld_name(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => non_empty(s),
+        Value::Object(obj) => obj.get("name").and_then(json_ld_name),
+        Value::Array(items) => {
+            let names: Vec<String> = items.iter().filter_map(json_ld_name).collect();
+            (!names.is_empty()).then(|| names.join(", "))
+        }
+        _ => None,
+    }
+}
+
+fn json_ld_field(json_ld: &[Value], key: &str) -> Option<String> {
+    json_ld
+        .iter()
+        .flat_map(json_ld_objects)
+        .find_map(|obj| obj.get(key).and_then(json_ld_name))
+}
+
+/// Parse `<title>`, meta tags, OpenGraph/Twitter cards, the canonical link,
+/// `lang` and JSON-LD. Relative canonical URLs are resolved against `base`.
+pub fn extract_metadata(html: &str, base: &Url) -> PageMetadata {
+    let doc = Html::parse_document(html);
+    let select = |css: &str| Selector::parse(css).expect("valid selector");
+    let mut named: BTreeMap<String, String> = BTreeMap::new();
+    let mut open_graph: BTreeMap<String, String> = BTreeMap::new();
+    let mut twitter: BTreeMap<String, String> = BTreeMap::new();
+    let mut json_ld: Vec<Value> = Vec::new();
+
+    for el in doc.select(&select("meta[content]")) {
+        let el = el.value();
+        let Some(key) = el.attr("property").or_else(|| el.attr("name")) else {
+            continue;
+        };
+        let Some(content) = el.attr("content").and_then(non_empty) else {
+            continue;
+        };
+        let key = key.trim().to_ascii_lowercase();
+        let map = if key.starts_with("og:") || key.starts_with("article:") {
+            &mut open_graph
+        } else if key.starts_with("twitter:") {
+            &mut twitter
+        } else {
+            &mut named
+        };
+        map.entry(key).or_insert(content);
+    }
+
+    for el in doc.select(&select(r#"script[type="application/ld+json"]"#)) {
+        let raw: String = el.text().collect();
+        if let Ok(value) = serde_json::from_str::<Value>(raw.trim()) {
+            json_ld.push(value);
+        }
+    }
+
+    let name = |key: &str| named.get(key).cloned();
+    let og = |key: &str| open_graph.get(key).cloned();
+    let tw = |key: &str| twitter.get(key).cloned();
+    let ld = |key: &str| json_ld_field(&json_ld, key);
+
+    let title = doc
+        .select(&select("title"))
+        .next()
+        .and_then(|el| non_empty(&el.text().collect::<String>()))
+        .or_else(|| og("og:title"))
+        .or_else(|| tw("twitter:title"))
+        .or_else(|| ld("headline"));
+    let description = name("description")
+        .or_else(|| og("og:description"))
+        .or_else(|| tw("twitter:description"))
+        .or_else(|| ld("description"));
+    let author = name("author")
+        .or_else(|| ld("author"))
+        .or_else(|| og("article:author"))
+        .or_else(|| tw("twitter:creator"));
+    let published = og("article:published_time")
+        .or_else(|| ld("datePublished"))
+        .or_else(|| name("date"));
+    let modified = og("article:modified_time")
+        .or_else(|| og("og:updated_time"))
+        .or_else(|| ld("dateModified"));
+    let site_name = og("og:site_name").or_else(|| ld("publisher"));
+    let canonical_url = doc
+        .select(&select("link[rel][href]"))
+        .find(|el| {
+            el.value().attr("rel").is_some_and(|rel| {
+                rel.split_ascii_whitespace()
+                    .any(|r| r.eq_ignore_ascii_case("canonical"))
+            })
+        })
+        .and_then(|el| el.value().attr("href"))
+        .map(str::to_string)
+        .or_else(|| og("og:url"))
+        .and_then(|href| base.join(href.trim()).ok())
+        .map(String::from);
+    let lang = doc.root_element().value().attr("lang").and_then(non_empty);
+
+    PageMetadata {
+        title,
+        description,
+        author,
+        published,
+        modified,
+        site_name,
+        canonical_url,
+        lang,
+        page_count: None,
+        open_graph,
+        twitter,
+        json_ld,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_head_metadata() {
+        let html = r#"<!doctype html><html lang="en-GB"><head>
+            <title> Ownership   explained </title>
+            <meta name="description" content="How Rust manages memory.">
+            <meta property="og:title" content="Ownership (OG)">
+            <meta property="og:site_name" content="The Book">
+            <meta property="article:published_time" content="2024-05-01T09:00:00Z">
+            <meta name="twitter:card" content="summary">
+            <link rel="alternate stylesheet" href="/print.css">
+            <link rel="Canonical" href="/book/ownership.html">
+            <script type="application/ld+json">{"@context": "https://schema.org", "@graph": [
+                {"@type": "Article", "author": [{"@type": "Person", "name": "Ada"}, "Grace"],
+                 "dateModified": "2024-06-01"}
+            ]}</script>
+            <script type="application/ld+json">{ not json</script>
+        </head><body></body></html>"#;
+        let base = Url::parse("https://doc.example.org/book/ch04.html").unwrap();
+        let meta = extract_metadata(html, &base);
+
+        assert_eq!(meta.title.as_deref(), Some("Ownership explained"));
+        assert_eq!(
+            meta.description.as_deref(),
+            Some("How Rust manages memory.")
+        );
+        assert_eq!(meta.author.as_deref(), Some("Ada, Grace"));
+        assert_eq!(meta.published.as_deref(), Some("2024-05-01T09:00:00Z"));
+        assert_eq!(meta.modified.as_deref(), Some("2024-06-01"));
+        assert_eq!(meta.site_name.as_deref(), Some("The Book"));
+        assert_eq!(
+            meta.canonical_url.as_deref(),
+            Some("https://doc.example.org/book/ownership.html")
+        );
+        assert_eq!(meta.lang.as_deref(), Some("en-GB"));
+        assert_eq!(meta.open_graph["og:title"], "Ownership (OG)");
+        assert_eq!(meta.twitter["twitter:card"], "summary");
+        assert_eq!(meta.json_ld.len(), 1);
+    }
+
+    #[test]
+    fn falls_back_to_social_cards() {
+        let html = r#"<html><head>
+            <meta property="og:title" content="OG title">
+            <meta property="og:url" content="https://example.com/canonical">
+            <meta name="twitter:description" content="Card description">
+        </head></html>"#;
+        let meta = extract_metadata(html, &Url::parse("https://example.com/a?b=c").unwrap());
+        assert_eq!(meta.title.as_deref(), Some("OG title"));
+        assert_eq!(meta.description.as_deref(), Some("Card description"));
+        assert_eq!(
+            meta.canonical_url.as_deref(),
+            Some("https://example.com/canonical")
+        );
+        assert_eq!(meta.lang, None);
+    }
+}
@@ -0,0 +1,243 @@
+use anyhow::{Result, anyhow};
+use pdf_extract::{Document, Object, PlainTextOutput};
+
+use crate::browse::BrowseFormat;
+
+/// Text and metadata extracted from a PDF document.
+#[derive(Debug, Default)]
+pub struct PdfText {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub page_count: usize,
+    /// Text of the leading pages; shorter than `page_count` if extraction stopped early.
+    pub pages: Vec<String>,
+}
+
+pub fn is_pdf(content_type: Option<&str>, body_prefix: &[u8]) -> bool {
+    let declared = content_type.is_some_and(|ct| {
+        ct.split(';')
+            .next()
+            .is_some_and(|mime| mime.trim().eq_ignore_ascii_case("application/pdf"))
+    });
+    declared || body_prefix.starts_with(b"%PDF-")
+}
+
+// PDF text strings are UTF-16BE with a BOM, or PDFDocEncoding (close enough to Latin-1).
+fn decode_text_string(bytes: &[u8]) -> String {
+    match bytes.strip_prefix(b"\xfe\xff") {
+        Some(utf16) => encoding_rs::UTF_16BE
+            .decode_without_bom_handling(utf16)
+            .0
+            .into_owned(),
+        None => bytes.iter().map(|&b| char::from(b)).collect(),
+    }
+}
+
+fn info_string(doc: &Document, key: &[u8]) -> Option<String> {
+    let info = match doc.trailer.get(b"Info").ok()? {
+        Object::Reference(id) => doc.get_object(*id).ok()?,
+        other => other,
+    };
+    let Object::String(bytes, _) = info.as_dict().ok()?.get(key).ok()? else {
+        return None;
+    };
+    let value = decode_text_string(bytes);
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Extract text page by page, stopping once `max_bytes` of text has been collected.
+///
+/// pdf-extract panics on some malformed documents, so this catches unwinds and
+/// reports them as errors. It is CPU-bound; call it from a blocking task.
+pub fn extract(body: &[u8], max_bytes: usize) -> Result<PdfText> {
+    std::panic::catch_unwind(|| extract_inner(body, max_bytes))
+        .map_err(|_| anyhow!("failed to parse PDF"))?
+}
+
+fn extract_inner(body: &[u8], max_bytes: usize) -> Result<PdfText> {
+    let mut doc = Document::load_mem(body).map_err(|e| anyhow!("failed to parse PDF: {e}"))?;
+    if doc.is_encrypted() {
+        doc.decrypt("")
+            .map_err(|e| anyhow!("PDF is encrypted and needs a password: {e}"))?;
+    }
+
+    let page_numbers: Vec<u32> = doc.get_pages().into_keys().collect();
+    let mut out = PdfText {
+        title: info_string(&doc, b"Title"),
+        author: info_string(&doc, b"Author"),
+        page_count: page_numbers.len(),
+        pages: Vec::new(),
+    };
+
+    let mut collected = 0;
+    for page in page_numbers {
+        if collected > max_bytes {
+            break;
+        }
+        let mut text = String::new();
+        pdf_extract::output_doc_page(&doc, &mut PlainTextOutput::new(&mut text), page)
+            .map_err(|e| anyhow!("failed to extract PDF page {page}: {e}"))?;
+        collected += text.len();
+        out.pages.push(text);
+    }
+    Ok(out)
+}
+
+fn tidy_page(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut blank_run = 0;
+    for line in text.lines() {
+        let line = line.trim_end();
+        if line.trim().is_empty() {
+            blank_run += 1;
+            continue;
+        }
+        if !out.is_empty() {
+            out.push_str(if blank_run > 0 { "\n\n" } else { "\n" });
+        }
+        out.push_str(line);
+        blank_run = 0;
+    }
+    out
+}
+
+pub fn render(pdf: &PdfText, format: BrowseFormat) -> String {
+    let mut out = String::new();
+    let truncated = if pdf.pages.len() < pdf.page_count {
+        format!(" (text of the first {} extracted)", pdf.pages.len())
+    } else {
+        String::new()
+    };
+
+    match format {
+        BrowseFormat::Markdown => {
+            if let Some(title) = &pdf.title {
+                out.push_str(&format!("# {title}\n\n"));
+            }
+            if let Some(author) = &pdf.author {
+                out.push_str(&format!("Author: {author}\n\n"));
+            }
+            out.push_str(&format!("Pages: {}{truncated}\n", pdf.page_count));
+            for (i, page) in pdf.pages.iter().enumerate() {
+                out.push_str(&format!("\n## Page {}\n\n{}\n", i + 1, tidy_page(page)));
+            }
+        }
+        BrowseFormat::Text | BrowseFormat::Links => {
+            if let Some(title) = &pdf.title {
+                out.push_str(&format!("Title: {title}\n"));
+            }
+            if let Some(author) = &pdf.author {
+                out.push_str(&format!("Author: {author}\n"));
+            }
+            out.push_str(&format!("Pages: {}{truncated}\n", pdf.page_count));
+            for (i, page) in pdf.pages.iter().enumerate() {
+                out.push_str(&format!(
+                    "\n--- Page {} ---\n\n{}\n",
+                    i + 1,
+                    tidy_page(page)
+                ));
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use pdf_extract::{Dictionary, Stream, StringFormat};
+
+    // Build a small PDF with one line of Helvetica text per page.
+    pub(crate) fn sample_pdf(pages: &[&str]) -> Vec<u8> {
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+        let font_id = doc.add_object(Dictionary::from_iter([
+            ("Type", Object::Name(b"Font".to_vec())),
+            ("Subtype", Object::Name(b"Type1".to_vec())),
+            ("BaseFont", Object::Name(b"Helvetica".to_vec())),
+        ]));
+        let resources_id = doc.add_object(Dictionary::from_iter([(
+            "Font",
+            Object::Dictionary(Dictionary::from_iter([("F1", Object::Reference(font_id))])),
+        )]));
+
+        let mut kids = Vec::new();
+        for text in pages {
+            let content = format!("BT /F1 12 Tf 72 720 Td ({text}) Tj ET");
+            let content_id = doc.add_object(Stream::new(Dictionary::new(), content.into_bytes()));
+            let page_id = doc.add_object(Dictionary::from_iter([
+                ("Type", Object::Name(b"Page".to_vec())),
+                ("Parent", Object::Reference(pages_id)),
+                ("Contents", Object::Reference(content_id)),
+            ]));
+            kids.push(Object::Reference(page_id));
+        }
+        let count = kids.len() as i64;
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(Dictionary::from_iter([
+                ("Type", Object::Name(b"Pages".to_vec())),
+                ("Kids", Object::Array(kids)),
+                ("Count", Object::Integer(count)),
+                ("Resources", Object::Reference(resources_id)),
+                (
+                    "MediaBox",
+                    Object::Array(vec![0.into(), 0.into(), 612.into(), 792.into()]),
+                ),
+            ])),
+        );
+        let catalog_id = doc.add_object(Dictionary::from_iter([
+            ("Type", Object::Name(b"Catalog".to_vec())),
+            ("Pages", Object::Reference(pages_id)),
+        ]));
+        let info_id = doc.add_object(Dictionary::from_iter([
+            (
+                "Title",
+                Object::String(b"Sample Paper".to_vec(), StringFormat::Literal),
+            ),
+            (
+                "Author",
+                Object::String(b"\xfe\xff\x00A\x00d\x00a".to_vec(), StringFormat::Literal),
+            ),
+        ]));
+        doc.trailer.set("Root", catalog_id);
+        doc.trailer.set("Info", info_id);
+
+        let mut buf = Vec::new();
+        doc.save_to(&mut buf).unwrap();
+        buf
+    }
+
+    #[test]
+    fn extracts_pages_and_metadata() {
+        let pdf = extract(&sample_pdf(&["First page", "Second page"]), 1_000_000).unwrap();
+        assert_eq!(pdf.title.as_deref(), Some("Sample Paper"));
+        assert_eq!(pdf.author.as_deref(), Some("Ada"));
+        assert_eq!(pdf.page_count, 2);
+        assert!(pdf.pages[0].contains("First page"));
+        assert!(pdf.pages[1].contains("Second page"));
+
+        let md = render(&pdf, BrowseFormat::Markdown);
+        assert!(md.starts_with("# Sample Paper\n\nAuthor: Ada\n\nPages: 2\n"));
+        assert!(md.contains("## Page 2\n\nSecond page"));
+    }
+
+    #[test]
+    fn stops_at_byte_budget() {
+        let pdf = extract(&sample_pdf(&["One", "Two", "Three"]), 1).unwrap();
+        assert_eq!(pdf.page_count, 3);
+        assert_eq!(pdf.pages.len(), 1);
+        assert!(
+            render(&pdf, BrowseFormat::Text).contains("Pages: 3 (text of the first 1 extracted)")
+        );
+    }
+
+    #[test]
+    fn detects_pdf_by_type_or_magic() {
+        assert!(is_pdf(Some("application/pdf; qs=0.001"), b""));
+        assert!(is_pdf(Some("application/octet-stream"), b"%PDF-1.7"));
+        assert!(!is_pdf(Some("text/html"), b"<html>"));
+        assert!(extract(b"not a pdf", 100).is_err());
+    }
+}
@@ -0,0 +1,157 @@
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+use futures_util::future::BoxFuture;
+use ipnet::IpNet;
+
+use crate::ip_policy::IpPolicy;
+
+/// Name resolution, abstracted so tests can stand in for DNS.
+pub trait HostLookup: std::fmt::Debug + Send + Sync + 'static {
+    fn lookup(&self, host: &str) -> BoxFuture<'static, io::Result<Vec<IpAddr>>>;
+}
+
+/// The system resolver, via `tokio::net::lookup_host`.
+#[derive(Debug, Default)]
+pub struct SystemLookup;
+
+impl HostLookup for SystemLookup {
+    fn lookup(&self, host: &str) -> BoxFuture<'static, io::Result<Vec<IpAddr>>> {
+        let host = host.to_string();
+        Box::pin(async move {
+            Ok(tokio::net::lookup_host((host.as_str(), 0))
+                .await?
+                .map(|addr| addr.ip())
+                .collect())
+        })
+    }
+}
+
+/// Resolves names and drops denied addresses at connect time.
+///
+/// Checking a name before the request is not enough: the HTTP client resolves
+/// it again, and a DNS-rebinding host can answer with a public address the
+/// first time and a private one the second. Handing this resolver to the
+/// client means the addresses it connects to are the ones that were vetted.
+#[derive(Debug, Clone)]
+pub struct PinnedResolver {
+    lookup: Arc<dyn HostLookup>,
+    policy: IpPolicy,
+}
+
+impl PinnedResolver {
+    pub fn new(lookup: Arc<dyn HostLookup>, policy: IpPolicy) -> Self {
+        Self { lookup, policy }
+    }
+
+    /// The deny range `ip` falls in, if any.
+    pub fn denied_by(&self, ip: IpAddr) -> Option<IpNet> {
+        self.policy.denied_by(ip)
+    }
+
+    /// Allowed addresses for `host`; an error if there are none.
+    pub async fn resolve(&self, host: &str) -> io::Result<Vec<IpAddr>> {
+        let addrs = self.lookup.lookup(host).await?;
+        if addrs.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("host '{host}' did not resolve"),
+            ));
+        }
+        let mut denied = None;
+        let mut vetted = Vec::with_capacity(addrs.len());
+        for ip in addrs {
+            match self.denied_by(ip) {
+                Some(net) => denied = denied.or(Some((ip, net))),
+                None => vetted.push(ip),
+            }
+        }
+        match denied {
+            Some((ip, net)) if vetted.is_empty() => Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!(
+                    "refusing to connect to '{host}': it resolves only to denied addresses ({ip} is in {net})"
+                ),
+            )),
+            _ => Ok(vetted),
+        }
+    }
+}
+
+impl reqwest::dns::Resolve for PinnedResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        let resolver = self.clone();
+        Box::pin(async move {
+            let addrs = PinnedResolver::resolve(&resolver, name.as_str()).await?;
+            // Port 0 is replaced with the URL's port by the client.
+            let addrs: reqwest::dns::Addrs =
+                Box::new(addrs.into_iter().map(|ip| SocketAddr::new(ip, 0)));
+            Ok(addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    use super::*;
+
+    /// Answers from a table; each name's answers are used in turn, then the last one repeats.
+    #[derive(Debug, Default)]
+    pub(crate) struct FakeLookup {
+        answers: Mutex<HashMap<String, Vec<Vec<IpAddr>>>>,
+    }
+
+    impl FakeLookup {
+        pub(crate) fn with(entries: &[(&str, &[&[&str]])]) -> Arc<Self> {
+            let answers = entries
+                .iter()
+                .map(|(host, answers)| {
+                    let answers = answers
+                        .iter()
+                        .map(|ips| ips.iter().map(|ip| ip.parse().unwrap()).collect())
+                        .collect();
+                    (host.to_string(), answers)
+                })
+                .collect();
+            Arc::new(Self {
+                answers: Mutex::new(answers),
+            })
+        }
+    }
+
+    impl HostLookup for FakeLookup {
+        fn lookup(&self, host: &str) -> BoxFuture<'static, io::Result<Vec<IpAddr>>> {
+            let mut answers = self.answers.lock().unwrap();
+            let answer = match answers.get_mut(host) {
+                Some(queue) if queue.len() > 1 => Ok(queue.remove(0)),
+                Some(queue) => Ok(queue[0].clone()),
+                None => Err(io::Error::new(io::ErrorKind::NotFound, "no such host")),
+            };
+            Box::pin(async move { answer })
+        }
+    }
+
+    #[tokio::test]
+    async fn filters_private_answers() {
+        let lookup = FakeLookup::with(&[
+            ("mixed.test", &[&["127.0.0.1", "93.184.216.34", "fd00::1"]]),
+            ("internal.test", &[&["10.0.0.5", "::1"]]),
+        ]);
+        let resolver = PinnedResolver::new(lookup, IpPolicy::default());
+
+        let mixed = resolver.resolve("mixed.test").await.unwrap();
+        assert_eq!(mixed, ["93.184.216.34".parse::<IpAddr>().unwrap()]);
+
+        let err = resolver.resolve("internal.test").await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        assert!(
+            err.to_string().contains("10.0.0.5 is in 10.0.0.0/8"),
+            "{err}"
+        );
+        assert!(resolver.resolve("missing.test").await.is_err());
+    }
+}
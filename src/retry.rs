@@ -0,0 +1,224 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use anyhow::{Result, anyhow};
+
+/// Exponential backoff with jitter.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total attempts including the first one; `1` disables retries.
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl RetryPolicy {
+    /// Delay before retry number `attempt` (1-based), or `None` to give up.
+    ///
+    /// A server-provided `Retry-After` is treated as a lower bound; if it exceeds
+    /// `max_delay` there is no point in retrying within this call.
+    pub fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Option<Duration> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+        if let Some(after) = retry_after
+            && after > self.max_delay
+        {
+            return None;
+        }
+
+        let exp = self
+            .base_delay
+            .saturating_mul(1u32 << (attempt - 1).min(16))
+            .min(self.max_delay);
+        // Equal jitter: at least half the exponential delay, so retries stay spaced out.
+        let half = exp / 2;
+        let jitter = Duration::from_millis(fastrand::u64(0..=half.as_millis() as u64));
+        let delay = half + jitter;
+
+        Some(retry_after.map_or(delay, |after| delay.max(after)))
+    }
+}
+
+#[derive(Debug, Default)]
+struct BreakerState {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+    trial_started: Option<Instant>,
+}
+
+/// Fails calls fast after `threshold` consecutive failures, for `cooldown`.
+///
+/// Once the cooldown elapses a single trial call is let through (half-open);
+/// its outcome closes the breaker again or re-opens it. A trial that never
+/// reports back (e.g. a cancelled request) is replaced after another cooldown.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    threshold: u32,
+    cooldown: Duration,
+    state: Mutex<BreakerState>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+impl CircuitState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CircuitState::Closed => "closed",
+            CircuitState::Open => "open",
+            CircuitState::HalfOpen => "half_open",
+        }
+    }
+}
+
+impl CircuitBreaker {
+    /// A `threshold` of zero disables the breaker.
+    pub fn new(threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            threshold,
+            cooldown,
+            state: Mutex::new(BreakerState::default()),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BreakerState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn state(&self) -> CircuitState {
+        let state = self.lock();
+        match state.open_until {
+            None => CircuitState::Closed,
+            Some(until) if until > Instant::now() => CircuitState::Open,
+            Some(_) => CircuitState::HalfOpen,
+        }
+    }
+
+    /// Admit a call, or fail fast with a labeled error while the circuit is open.
+    pub fn check(&self, what: &str) -> Result<()> {
+        self.check_at(what, Instant::now())
+    }
+
+    fn check_at(&self, what: &str, now: Instant) -> Result<()> {
+        if self.threshold == 0 {
+            return Ok(());
+        }
+        let mut state = self.lock();
+        let Some(until) = state.open_until else {
+            return Ok(());
+        };
+        if until > now {
+            let wait = until - now;
+            return Err(anyhow!(
+                "{what} circuit breaker open after {} consecutive failures; retry in {}s",
+                state.consecutive_failures,
+                wait.as_secs().max(1)
+            ));
+        }
+        if let Some(started) = state.trial_started
+            && started + self.cooldown > now
+        {
+            return Err(anyhow!(
+                "{what} circuit breaker half-open; waiting on trial request"
+            ));
+        }
+        state.trial_started = Some(now);
+        Ok(())
+    }
+
+    pub fn record_success(&self) {
+        *self.lock() = BreakerState::default();
+    }
+
+    pub fn record_failure(&self) {
+        self.record_failure_at(Instant::now());
+    }
+
+    fn record_failure_at(&self, now: Instant) {
+        if self.threshold == 0 {
+            return;
+        }
+        let mut state = self.lock();
+        state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+        if state.trial_started.is_some() || state.consecutive_failures >= self.threshold {
+            state.open_until = Some(now + self.cooldown);
+        }
+        state.trial_started = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_grows_and_respects_bounds() {
+        let policy = RetryPolicy {
+            max_attempts: 4,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(300),
+        };
+
+        let first = policy.delay(1, None).unwrap();
+        assert!(first >= Duration::from_millis(50) && first <= Duration::from_millis(100));
+        let third = policy.delay(3, None).unwrap();
+        assert!(third >= Duration::from_millis(150) && third <= Duration::from_millis(300));
+        assert!(policy.delay(4, None).is_none());
+
+        let honored = policy.delay(1, Some(Duration::from_millis(250))).unwrap();
+        assert!(honored >= Duration::from_millis(250));
+        assert!(policy.delay(1, Some(Duration::from_secs(10))).is_none());
+    }
+
+    #[test]
+    fn breaker_opens_then_admits_one_trial() {
+        let breaker = CircuitBreaker::new(2, Duration::from_secs(30));
+        let now = Instant::now();
+
+        breaker.record_failure_at(now);
+        assert!(breaker.check_at("searxng", now).is_ok());
+        breaker.record_failure_at(now);
+
+        let err = breaker.check_at("searxng", now).unwrap_err();
+        assert!(err.to_string().contains("circuit breaker open"));
+
+        let later = now + Duration::from_secs(31);
+        assert!(breaker.check_at("searxng", later).is_ok());
+        assert!(breaker.check_at("searxng", later).is_err());
+
+        // An abandoned trial is replaced after another cooldown.
+        let much_later = later + Duration::from_secs(31);
+        assert!(breaker.check_at("searxng", much_later).is_ok());
+
+        breaker.record_success();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert!(breaker.check_at("searxng", later).is_ok());
+    }
+
+    #[test]
+    fn failed_trial_reopens_breaker() {
+        let breaker = CircuitBreaker::new(3, Duration::from_secs(30));
+        let now = Instant::now();
+        for _ in 0..3 {
+            breaker.record_failure_at(now);
+        }
+        let later = now + Duration::from_secs(31);
+        assert!(breaker.check_at("searxng", later).is_ok());
+        breaker.record_failure_at(later);
+        assert!(breaker.check_at("searxng", later).is_err());
+    }
+
+    #[test]
+    fn zero_threshold_disables_breaker() {
+        let breaker = CircuitBreaker::new(0, Duration::from_secs(30));
+        for _ in 0..10 {
+            breaker.record_failure();
+        }
+        assert!(breaker.check("searxng").is_ok());
+    }
+}
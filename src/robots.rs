@@ -0,0 +1,343 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use reqwest::Url;
+
+use crate::cache::TtlCache;
+use crate::limits::BrowseBusy;
+
+/// How long fetched robots.txt rules are reused.
+const ROBOTS_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+const ROBOTS_CACHE_MAX_ENTRIES: usize = 256;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Rule {
+    allow: bool,
+    pattern: String,
+}
+
+/// The robots.txt group that applies to one user-agent.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RobotsRules {
+    rules: Vec<Rule>,
+    pub crawl_delay: Option<Duration>,
+}
+
+impl RobotsRules {
+    /// What an unreachable robots.txt means (RFC 9309, section 2.3.1.4).
+    pub fn disallow_all() -> Self {
+        Self {
+            rules: vec![Rule {
+                allow: false,
+                pattern: "/".to_string(),
+            }],
+            crawl_delay: None,
+        }
+    }
+
+    /// Parse robots.txt, keeping the groups for `agent` (a product token such
+    /// as `searxng-mcp`), or the `*` groups if none name it.
+    pub fn parse(text: &str, agent: &str) -> Self {
+        let agent = agent.to_ascii_lowercase();
+        let mut specific: Option<Self> = None;
+        let mut wildcard: Option<Self> = None;
+        let mut group_agents: Vec<String> = Vec::new();
+        let mut in_rules = false;
+
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let key = key.trim().to_ascii_lowercase();
+            let value = value.trim();
+
+            if key == "user-agent" {
+                // A user-agent line after rules starts a new group.
+                if in_rules {
+                    group_agents.clear();
+                    in_rules = false;
+                }
+                let name = value.to_ascii_lowercase();
+                if name == agent {
+                    specific.get_or_insert_with(Self::default);
+                } else if name == "*" {
+                    wildcard.get_or_insert_with(Self::default);
+                }
+                group_agents.push(name);
+                continue;
+            }
+            if !matches!(key.as_str(), "allow" | "disallow" | "crawl-delay") {
+                continue;
+            }
+            in_rules = true;
+
+            for (name, target) in [(agent.as_str(), &mut specific), ("*", &mut wildcard)] {
+                if !group_agents.iter().any(|a| a == name) {
+                    continue;
+                }
+                let Some(target) = target.as_mut() else {
+                    continue;
+                };
+                match key.as_str() {
+                    "crawl-delay" => {
+                        if let Ok(secs) = value.parse::<f64>()
+                            && secs.is_finite()
+                            && secs >= 0.0
+                        {
+                            target.crawl_delay = Some(Duration::from_secs_f64(secs));
+                        }
+                    }
+                    // An empty Disallow allows everything; it is not a rule.
+                    _ if value.is_empty() => {}
+                    _ => target.rules.push(Rule {
+                        allow: key == "allow",
+                        pattern: value.to_string(),
+                    }),
+                }
+            }
+        }
+        specific.or(wildcard).unwrap_or_default()
+    }
+
+    /// The most specific (longest) matching rule decides; `Allow` wins ties.
+    pub fn is_allowed(&self, path: &str) -> bool {
+        if path == "/robots.txt" {
+            return true;
+        }
+        self.rules
+            .iter()
+            .filter(|rule| pattern_matches(&rule.pattern, path))
+            .max_by_key(|rule| (rule.pattern.len(), rule.allow))
+            .is_none_or(|rule| rule.allow)
+    }
+}
+
+/// robots.txt path matching: a prefix match where `*` matches any run of
+/// characters and a trailing `$` anchors the end.
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+    let mut parts = pattern.split('*');
+    let Some(mut rest) = path.strip_prefix(parts.next().unwrap_or_default()) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    for (i, part) in parts.iter().enumerate() {
+        if anchored && i + 1 == parts.len() {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(at) => rest = &rest[at + part.len()..],
+            None => return false,
+        }
+    }
+    !anchored || rest.is_empty()
+}
+
+/// Returned when robots.txt disallows a URL for our user-agent.
+#[derive(Debug)]
+pub struct RobotsDisallowed {
+    pub url: String,
+    pub agent: String,
+}
+
+impl std::fmt::Display for RobotsDisallowed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "blocked by robots.txt: {} is disallowed for user-agent '{}'",
+            self.url, self.agent
+        )
+    }
+}
+
+impl std::error::Error for RobotsDisallowed {}
+
+/// robots.txt state shared by all browse calls: cached rules per origin and
+/// the earliest time each host may be fetched again under its `Crawl-delay`.
+#[derive(Debug)]
+pub struct Robots {
+    agent: String,
+    rules: TtlCache<String, Arc<RobotsRules>>,
+    next_slot: Mutex<HashMap<String, Instant>>,
+}
+
+impl Robots {
+    pub fn new(user_agent: &str) -> Self {
+        Self {
+            agent: product_token(user_agent),
+            rules: TtlCache::new(ROBOTS_CACHE_MAX_ENTRIES, ROBOTS_TTL),
+            next_slot: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The user-agent name matched against robots.txt groups.
+    pub fn agent(&self) -> &str {
+        &self.agent
+    }
+
+    pub fn cached(&self, origin: &str) -> Option<Arc<RobotsRules>> {
+        self.rules.get(&origin.to_string())
+    }
+
+    pub fn insert(&self, origin: String, rules: Arc<RobotsRules>) {
+        self.rules.insert(origin, rules);
+    }
+
+    /// Refuse `url` if `rules` disallow it, otherwise wait out the host's crawl
+    /// delay. A wait longer than `max_wait` fails with [`BrowseBusy`].
+    pub async fn check(&self, url: &Url, rules: &RobotsRules, max_wait: Duration) -> Result<()> {
+        let mut path = url.path().to_string();
+        if let Some(query) = url.query() {
+            path.push('?');
+            path.push_str(query);
+        }
+        if !rules.is_allowed(&path) {
+            return Err(RobotsDisallowed {
+                url: url.to_string(),
+                agent: self.agent.clone(),
+            }
+            .into());
+        }
+        if let (Some(delay), Some(host)) = (rules.crawl_delay, url.host_str()) {
+            let wait = self
+                .reserve_slot(host, delay, max_wait, Instant::now())
+                .map_err(|wait| {
+                    BrowseBusy::new(format!(
+                        "{host} asks for a {} ms robots.txt Crawl-delay; the next slot is {} ms away (BROWSE_QUEUE_WAIT_MS={})",
+                        delay.as_millis(),
+                        wait.as_millis(),
+                        max_wait.as_millis()
+                    ))
+                })?;
+            if !wait.is_zero() {
+                tracing::debug!(
+                    host,
+                    wait_ms = wait.as_millis(),
+                    "waiting for robots.txt crawl delay"
+                );
+                tokio::time::sleep(wait).await;
+            }
+        }
+        Ok(())
+    }
+
+    /// Claim the host's next free slot and return how long to wait for it;
+    /// `Err` carries a wait beyond `max_wait`, and nothing is claimed.
+    fn reserve_slot(
+        &self,
+        host: &str,
+        delay: Duration,
+        max_wait: Duration,
+        now: Instant,
+    ) -> Result<Duration, Duration> {
+        let host = host.to_ascii_lowercase();
+        let mut slots = self.next_slot.lock().unwrap_or_else(|e| e.into_inner());
+        slots.retain(|_, next| *next > now);
+        let start = slots.get(&host).copied().unwrap_or(now).max(now);
+        let wait = start - now;
+        if wait > max_wait {
+            return Err(wait);
+        }
+        slots.insert(host, start + delay);
+        Ok(wait)
+    }
+}
+
+/// `searxng-mcp/0.2.0 (+https://...)` -> `searxng-mcp`.
+fn product_token(user_agent: &str) -> String {
+    user_agent
+        .trim()
+        .split(|c: char| c == '/' || c.is_whitespace())
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROBOTS: &str = "\
+# Example
+User-agent: *
+Disallow: /private/
+Allow: /private/press/
+Disallow: /*.pdf$
+Crawl-delay: 2
+
+User-agent: searxng-mcp
+User-agent: OtherBot
+Disallow: /search
+Allow: /search/about
+Disallow:
+Crawl-delay: 0.5
+";
+
+    #[test]
+    fn picks_the_group_for_our_agent() {
+        let ours = RobotsRules::parse(ROBOTS, "searxng-mcp");
+        assert!(!ours.is_allowed("/search?q=rust"));
+        assert!(ours.is_allowed("/search/about"));
+        assert!(ours.is_allowed("/private/secret"));
+        assert_eq!(ours.crawl_delay, Some(Duration::from_millis(500)));
+
+        let other = RobotsRules::parse(ROBOTS, "somebot");
+        assert!(!other.is_allowed("/private/secret"));
+        assert!(other.is_allowed("/private/press/release"));
+        assert!(!other.is_allowed("/files/report.pdf"));
+        assert!(other.is_allowed("/files/report.pdf?download=1"));
+        assert!(other.is_allowed("/search"));
+        assert!(other.is_allowed("/robots.txt"));
+        assert_eq!(other.crawl_delay, Some(Duration::from_secs(2)));
+
+        assert!(RobotsRules::parse("", "searxng-mcp").is_allowed("/anything"));
+        assert!(!RobotsRules::disallow_all().is_allowed("/"));
+    }
+
+    #[test]
+    fn matches_wildcards_and_anchors() {
+        assert!(pattern_matches("/a*/c", "/abc/c/d"));
+        assert!(pattern_matches("/*.php$", "/x/index.php"));
+        assert!(!pattern_matches("/*.php$", "/x/index.php5"));
+        assert!(pattern_matches("/exact$", "/exact"));
+        assert!(!pattern_matches("/exact$", "/exactly"));
+        assert!(!pattern_matches("/b", "/a/b"));
+    }
+
+    #[test]
+    fn spaces_requests_by_crawl_delay() {
+        let robots = Robots::new("searxng-mcp/0.2.0");
+        assert_eq!(robots.agent(), "searxng-mcp");
+        let now = Instant::now();
+        let delay = Duration::from_secs(2);
+        let max = Duration::from_secs(5);
+        let reserve = |host, now| robots.reserve_slot(host, delay, max, now);
+        assert_eq!(reserve("a.example", now), Ok(Duration::ZERO));
+        assert_eq!(reserve("a.example", now), Ok(delay));
+        assert_eq!(reserve("a.example", now), Ok(delay * 2));
+        assert_eq!(reserve("a.example", now), Err(delay * 3));
+        assert_eq!(reserve("b.example", now), Ok(Duration::ZERO));
+        assert_eq!(
+            reserve("a.example", now + Duration::from_secs(10)),
+            Ok(Duration::ZERO)
+        );
+    }
+
+    #[tokio::test]
+    async fn long_crawl_delay_reports_busy() {
+        let robots = Robots::new("searxng-mcp/0.2.0");
+        let rules = RobotsRules::parse("User-agent: *\nCrawl-delay: 60\n", "searxng-mcp");
+        let url = Url::parse("https://slow.example/page").unwrap();
+        let max_wait = Duration::from_secs(1);
+        robots.check(&url, &rules, max_wait).await.unwrap();
+        let err = robots.check(&url, &rules, max_wait).await.unwrap_err();
+        assert!(err.downcast_ref::<BrowseBusy>().is_some(), "{err:#}");
+        assert!(err.to_string().contains("Crawl-delay"), "{err:#}");
+    }
+}
@@ -1,15 +1,29 @@
 use std::collections::HashMap;
-use std::time::Duration;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
 
 use anyhow::{Context, Result, anyhow};
-use reqwest::header::{HeaderMap, HeaderValue, USER_AGENT};
+use futures_util::future::join_all;
+use reqwest::header::{HeaderMap, HeaderValue, RETRY_AFTER, USER_AGENT};
 use serde::{Deserialize, Serialize};
 use url::Url;
 
+use crate::retry::{CircuitBreaker, CircuitState, RetryPolicy};
+
 const DEFAULT_BASE_URL: &str = "http://localhost:8080";
 const DEFAULT_LANGUAGE: &str = "en";
 const DEFAULT_NUM_RESULTS: usize = 5;
 const DEFAULT_TIMEOUT_SECS: u64 = 20;
+const DEFAULT_MAX_PAGES: u32 = 5;
+const DEFAULT_CACHE_TTL_SECS: u64 = 60;
+const DEFAULT_CACHE_MAX_ENTRIES: usize = 256;
+const DEFAULT_EJECT_SECS: u64 = 30;
+const DEFAULT_RETRY_MAX_ATTEMPTS: u32 = 3;
+const DEFAULT_RETRY_BASE_DELAY_MS: u64 = 250;
+const DEFAULT_RETRY_MAX_DELAY_MS: u64 = 5_000;
+const DEFAULT_BREAKER_THRESHOLD: u32 = 5;
+const DEFAULT_BREAKER_COOLDOWN_SECS: u64 = 30;
 
 fn parse_csv(s: &str) -> Vec<String> {
     s.split(',')
@@ -44,39 +58,102 @@ pub enum EngineFilter {
     All,
 }
 
+/// How searches are spread across multiple configured SearXNG instances.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstanceStrategy {
+    /// Use instances in configured order, moving on when one fails.
+    Failover,
+    /// Rotate the starting instance per request, still failing over on errors.
+    RoundRobin,
+    /// Query every available instance concurrently and merge the results.
+    FanOut,
+}
+
+impl InstanceStrategy {
+    fn parse(value: &str) -> Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "failover" => Ok(Self::Failover),
+            "roundrobin" | "round_robin" | "round-robin" => Ok(Self::RoundRobin),
+            "fanout" | "fan_out" | "fan-out" => Ok(Self::FanOut),
+            other => Err(anyhow!(
+                "invalid SEARXNG_STRATEGY '{other}' (valid: failover,roundrobin,fanout)"
+            )),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Failover => "failover",
+            Self::RoundRobin => "roundrobin",
+            Self::FanOut => "fanout",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SearxngConfig {
-    pub base_url: String,
+    pub base_urls: Vec<String>,
+    pub strategy: InstanceStrategy,
+    pub eject_for: Duration,
     pub default_categories: Vec<String>,
     pub default_engines: Vec<String>,
     pub language: String,
     pub safe_search: SafeSearch,
     pub num_results: usize,
     pub timeout: Duration,
+    pub aggregate_pages: bool,
+    pub max_pages: u32,
+    pub cache_ttl: Duration,
+    pub cache_max_entries: usize,
+    pub retry: RetryPolicy,
+    pub breaker_threshold: u32,
+    pub breaker_cooldown: Duration,
 }
 
 impl Default for SearxngConfig {
     fn default() -> Self {
         Self {
-            base_url: DEFAULT_BASE_URL.to_string(),
+            base_urls: vec![DEFAULT_BASE_URL.to_string()],
+            strategy: InstanceStrategy::Failover,
+            eject_for: Duration::from_secs(DEFAULT_EJECT_SECS),
             default_categories: Vec::new(),
             default_engines: Vec::new(),
             language: DEFAULT_LANGUAGE.to_string(),
             safe_search: SafeSearch::None,
             num_results: DEFAULT_NUM_RESULTS,
             timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECS),
+            aggregate_pages: false,
+            max_pages: DEFAULT_MAX_PAGES,
+            cache_ttl: Duration::from_secs(DEFAULT_CACHE_TTL_SECS),
+            cache_max_entries: DEFAULT_CACHE_MAX_ENTRIES,
+            retry: RetryPolicy {
+                max_attempts: DEFAULT_RETRY_MAX_ATTEMPTS,
+                base_delay: Duration::from_millis(DEFAULT_RETRY_BASE_DELAY_MS),
+                max_delay: Duration::from_millis(DEFAULT_RETRY_MAX_DELAY_MS),
+            },
+            breaker_threshold: DEFAULT_BREAKER_THRESHOLD,
+            breaker_cooldown: Duration::from_secs(DEFAULT_BREAKER_COOLDOWN_SECS),
         }
     }
 }
 
 impl SearxngConfig {
-    pub fn from_env() -> Self {
+    pub fn from_env() -> Result<Self> {
         let mut cfg = Self::default();
 
-        if let Ok(v) = std::env::var("SEARXNG_BASE_URL")
-            && !v.trim().is_empty()
+        if let Ok(v) = std::env::var("SEARXNG_BASE_URL") {
+            let urls = parse_csv(&v);
+            if !urls.is_empty() {
+                cfg.base_urls = urls;
+            }
+        }
+        if let Ok(v) = std::env::var("SEARXNG_STRATEGY") {
+            cfg.strategy = InstanceStrategy::parse(&v)?;
+        }
+        if let Ok(v) = std::env::var("SEARXNG_EJECT_SECS")
+            && let Ok(secs) = v.trim().parse::<u64>()
         {
-            cfg.base_url = v;
+            cfg.eject_for = Duration::from_secs(secs);
         }
         if let Ok(v) = std::env::var("SEARXNG_DEFAULT_CATEGORIES") {
             cfg.default_categories = parse_csv(&v);
@@ -102,31 +179,626 @@ impl SearxngConfig {
         {
             cfg.timeout = Duration::from_secs(secs);
         }
+        if let Ok(v) = std::env::var("SEARXNG_AGGREGATE_PAGES") {
+            cfg.aggregate_pages = matches!(
+                v.trim().to_ascii_lowercase().as_str(),
+                "1" | "true" | "yes" | "on"
+            );
+        }
+        if let Ok(v) = std::env::var("SEARXNG_MAX_PAGES")
+            && let Ok(n) = v.trim().parse::<u32>()
+            && n > 0
+        {
+            cfg.max_pages = n;
+        }
+        if let Ok(v) = std::env::var("SEARXNG_CACHE_TTL_SECS")
+            && let Ok(secs) = v.trim().parse::<u64>()
+        {
+            cfg.cache_ttl = Duration::from_secs(secs);
+        }
+        if let Ok(v) = std::env::var("SEARXNG_CACHE_MAX_ENTRIES")
+            && let Ok(n) = v.trim().parse::<usize>()
+        {
+            cfg.cache_max_entries = n;
+        }
+        if let Ok(v) = std::env::var("SEARXNG_RETRY_MAX_ATTEMPTS")
+            && let Ok(n) = v.trim().parse::<u32>()
+        {
+            cfg.retry.max_attempts = n.max(1);
+        }
+        if let Ok(v) = std::env::var("SEARXNG_RETRY_BASE_DELAY_MS")
+            && let Ok(ms) = v.trim().parse::<u64>()
+        {
+            cfg.retry.base_delay = Duration::from_millis(ms);
+        }
+        if let Ok(v) = std::env::var("SEARXNG_RETRY_MAX_DELAY_MS")
+            && let Ok(ms) = v.trim().parse::<u64>()
+        {
+            cfg.retry.max_delay = Duration::from_millis(ms);
+        }
+        if let Ok(v) = std::env::var("SEARXNG_BREAKER_THRESHOLD")
+            && let Ok(n) = v.trim().parse::<u32>()
+        {
+            cfg.breaker_threshold = n;
+        }
+        if let Ok(v) = std::env::var("SEARXNG_BREAKER_COOLDOWN_SECS")
+            && let Ok(secs) = v.trim().parse::<u64>()
+        {
+            cfg.breaker_cooldown = Duration::from_secs(secs);
+        }
 
-        cfg
+        Ok(cfg)
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+#[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
 pub struct SearchResult {
     pub title: String,
     pub url: String,
-    #[serde(default)]
     pub content: String,
-    #[serde(default)]
     pub score: f64,
-    #[serde(default)]
     pub engines: Vec<String>,
-    #[serde(default)]
     pub category: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumbnail: Option<String>,
+    /// Template-specific fields for image, video, map and science results.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<ResultDetails>,
+}
+
+#[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ResultDetails {
+    Image(ImageDetails),
+    Video(VideoDetails),
+    Map(MapDetails),
+    Paper(PaperDetails),
+}
+
+#[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
+pub struct ImageDetails {
+    pub img_src: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumbnail_src: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolution: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub img_format: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
+pub struct VideoDetails {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iframe_src: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
+pub struct MapDetails {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latitude: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub longitude: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
+pub struct PaperDetails {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doi: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub authors: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub journal: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publisher: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pdf_url: Option<String>,
+}
+
+// SearXNG results are flat objects whose extra fields depend on `template`;
+// engines disagree on types, so everything beyond the basics is read leniently.
+#[derive(Deserialize)]
+struct RawSearchResult {
+    title: String,
+    url: String,
+    #[serde(default)]
+    content: String,
+    #[serde(default)]
+    score: f64,
+    #[serde(default)]
+    engines: Vec<String>,
+    #[serde(default)]
+    category: String,
+    #[serde(default, deserialize_with = "lenient_string")]
+    template: Option<String>,
+    #[serde(default, rename = "publishedDate", deserialize_with = "lenient_string")]
+    published_date: Option<String>,
+    #[serde(default, deserialize_with = "lenient_string")]
+    thumbnail: Option<String>,
+    #[serde(default, deserialize_with = "lenient_string")]
+    img_src: Option<String>,
+    #[serde(default, deserialize_with = "lenient_string")]
+    thumbnail_src: Option<String>,
+    #[serde(default, deserialize_with = "lenient_string")]
+    resolution: Option<String>,
+    #[serde(default, deserialize_with = "lenient_string")]
+    img_format: Option<String>,
+    #[serde(default, deserialize_with = "lenient_string")]
+    source: Option<String>,
+    #[serde(default, deserialize_with = "lenient_string")]
+    author: Option<String>,
+    #[serde(default, deserialize_with = "lenient_string")]
+    length: Option<String>,
+    // Some engines send `duration` instead of (or as well as) `length`.
+    #[serde(default, deserialize_with = "lenient_string")]
+    duration: Option<String>,
+    #[serde(default, deserialize_with = "lenient_string")]
+    iframe_src: Option<String>,
+    #[serde(default, deserialize_with = "lenient_f64")]
+    latitude: Option<f64>,
+    #[serde(default, deserialize_with = "lenient_f64")]
+    longitude: Option<f64>,
+    #[serde(default)]
+    address: Option<serde_json::Value>,
+    #[serde(default, deserialize_with = "lenient_string")]
+    doi: Option<String>,
+    #[serde(default, deserialize_with = "lenient_strings")]
+    authors: Vec<String>,
+    #[serde(default, deserialize_with = "lenient_string")]
+    journal: Option<String>,
+    #[serde(default, deserialize_with = "lenient_string")]
+    publisher: Option<String>,
+    #[serde(default, deserialize_with = "lenient_string")]
+    pdf_url: Option<String>,
+}
+
+impl From<RawSearchResult> for SearchResult {
+    fn from(raw: RawSearchResult) -> Self {
+        let template = raw.template.as_deref().unwrap_or_default();
+        let details = match template.trim_end_matches(".html") {
+            "images" => raw.img_src.map(|img_src| {
+                ResultDetails::Image(ImageDetails {
+                    img_src,
+                    thumbnail_src: raw.thumbnail_src,
+                    resolution: raw.resolution,
+                    img_format: raw.img_format,
+                    source: raw.source,
+                    author: raw.author,
+                })
+            }),
+            "videos" => Some(ResultDetails::Video(VideoDetails {
+                duration: raw.length.or(raw.duration),
+                iframe_src: raw.iframe_src,
+                author: raw.author,
+            })),
+            "map" => Some(ResultDetails::Map(MapDetails {
+                latitude: raw.latitude,
+                longitude: raw.longitude,
+                address: raw.address.filter(|a| !a.is_null()),
+            })),
+            "paper" => Some(ResultDetails::Paper(PaperDetails {
+                doi: raw.doi,
+                authors: raw.authors,
+                journal: raw.journal,
+                publisher: raw.publisher,
+                pdf_url: raw.pdf_url,
+            })),
+            _ => None,
+        };
+
+        SearchResult {
+            title: raw.title,
+            url: raw.url,
+            content: raw.content,
+            score: raw.score,
+            engines: raw.engines,
+            category: raw.category,
+            published_date: raw.published_date,
+            thumbnail: raw.thumbnail,
+            details,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SearchResult {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        RawSearchResult::deserialize(deserializer).map(SearchResult::from)
+    }
+}
+
+fn lenient_string<'de, D>(deserializer: D) -> std::result::Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(
+        match Option::<serde_json::Value>::deserialize(deserializer)? {
+            Some(serde_json::Value::String(s)) if !s.trim().is_empty() => Some(s),
+            Some(serde_json::Value::Number(n)) => Some(n.to_string()),
+            _ => None,
+        },
+    )
+}
+
+fn lenient_text<'de, D>(deserializer: D) -> std::result::Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    lenient_string(deserializer).map(Option::unwrap_or_default)
+}
+
+fn lenient_f64<'de, D>(deserializer: D) -> std::result::Result<Option<f64>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(
+        match Option::<serde_json::Value>::deserialize(deserializer)? {
+            Some(serde_json::Value::Number(n)) => n.as_f64(),
+            Some(serde_json::Value::String(s)) => s.trim().parse().ok(),
+            _ => None,
+        },
+    )
+}
+
+/// Deserialize a list, dropping entries (and non-list values) that don't fit `T`.
+fn skip_invalid<'de, D, T>(deserializer: D) -> std::result::Result<Vec<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: serde::de::DeserializeOwned,
+{
+    Ok(
+        match Option::<serde_json::Value>::deserialize(deserializer)? {
+            Some(serde_json::Value::Array(items)) => items
+                .into_iter()
+                .filter_map(|v| serde_json::from_value(v).ok())
+                .collect(),
+            _ => Vec::new(),
+        },
+    )
+}
+
+fn lenient_strings<'de, D>(deserializer: D) -> std::result::Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(
+        match Option::<serde_json::Value>::deserialize(deserializer)? {
+            Some(serde_json::Value::Array(items)) => items
+                .into_iter()
+                .filter_map(|v| match v {
+                    serde_json::Value::String(s) if !s.trim().is_empty() => Some(s),
+                    _ => None,
+                })
+                .collect(),
+            Some(serde_json::Value::String(s)) if !s.trim().is_empty() => vec![s],
+            _ => Vec::new(),
+        },
+    )
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct Answer {
+    pub answer: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub engine: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct InfoboxUrl {
+    #[serde(default, deserialize_with = "lenient_text")]
+    pub title: String,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct InfoboxAttribute {
+    pub label: String,
+    #[serde(default, deserialize_with = "lenient_text")]
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct Infobox {
+    pub infobox: String,
+    #[serde(
+        default,
+        deserialize_with = "lenient_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub id: Option<String>,
+    #[serde(default, deserialize_with = "lenient_text")]
+    pub content: String,
+    #[serde(
+        default,
+        deserialize_with = "lenient_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub img_src: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "skip_invalid",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub urls: Vec<InfoboxUrl>,
+    #[serde(
+        default,
+        deserialize_with = "skip_invalid",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub attributes: Vec<InfoboxAttribute>,
+    #[serde(
+        default,
+        deserialize_with = "lenient_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub engine: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct UnresponsiveEngine {
+    pub engine: String,
+    pub error: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct SearxngResponse {
     #[serde(default)]
     pub results: Vec<SearchResult>,
     #[serde(default)]
     pub suggestions: Vec<String>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_answers",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub answers: Vec<Answer>,
+    #[serde(
+        default,
+        deserialize_with = "skip_invalid",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub infoboxes: Vec<Infobox>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub corrections: Vec<String>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_unresponsive_engines",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub unresponsive_engines: Vec<UnresponsiveEngine>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub number_of_results: Option<u64>,
+}
+
+impl SearxngResponse {
+    /// Collapse results whose URLs normalize to the same key, merging their engines.
+    fn dedupe_results(&mut self) {
+        let results = std::mem::take(&mut self.results);
+        let mut index: HashMap<String, usize> = HashMap::new();
+        for result in results {
+            self.push_result(&mut index, result);
+        }
+    }
+
+    fn push_result(&mut self, index: &mut HashMap<String, usize>, result: SearchResult) {
+        let key = normalize_result_url(&result.url);
+        match index.get(&key) {
+            Some(&i) => {
+                let existing = &mut self.results[i];
+                if result.score > existing.score {
+                    existing.score = result.score;
+                }
+                for engine in result.engines {
+                    if !existing.engines.contains(&engine) {
+                        existing.engines.push(engine);
+                    }
+                }
+            }
+            None => {
+                index.insert(key, self.results.len());
+                self.results.push(result);
+            }
+        }
+    }
+
+    /// Fold a later page into this response.
+    fn merge_page(&mut self, page: SearxngResponse) {
+        let mut index: HashMap<String, usize> = self
+            .results
+            .iter()
+            .enumerate()
+            .map(|(i, r)| (normalize_result_url(&r.url), i))
+            .collect();
+        for result in page.results {
+            self.push_result(&mut index, result);
+        }
+
+        for suggestion in page.suggestions {
+            if !self.suggestions.contains(&suggestion) {
+                self.suggestions.push(suggestion);
+            }
+        }
+        for correction in page.corrections {
+            if !self.corrections.contains(&correction) {
+                self.corrections.push(correction);
+            }
+        }
+        for engine in page.unresponsive_engines {
+            if !self
+                .unresponsive_engines
+                .iter()
+                .any(|e| e.engine == engine.engine)
+            {
+                self.unresponsive_engines.push(engine);
+            }
+        }
+        if self.answers.is_empty() {
+            self.answers = page.answers;
+        }
+        if self.infoboxes.is_empty() {
+            self.infoboxes = page.infoboxes;
+        }
+        self.number_of_results = self.number_of_results.max(page.number_of_results);
+    }
+
+    /// Drop everything except `results` and `suggestions`.
+    pub fn strip_extras(&mut self) {
+        self.answers.clear();
+        self.infoboxes.clear();
+        self.corrections.clear();
+        self.unresponsive_engines.clear();
+        self.number_of_results = None;
+    }
+}
+
+// Older SearXNG releases emit answers as plain strings, newer ones as objects;
+// answer templates without a textual `answer` (translations, weather, ...) are
+// skipped rather than failing the whole response.
+fn deserialize_answers<'de, D>(deserializer: D) -> std::result::Result<Vec<Answer>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    fn text(value: Option<&serde_json::Value>) -> Option<String> {
+        match value? {
+            serde_json::Value::String(s) if !s.trim().is_empty() => Some(s.clone()),
+            serde_json::Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        }
+    }
+
+    let raw = match Option::<serde_json::Value>::deserialize(deserializer)? {
+        Some(serde_json::Value::Array(items)) => items,
+        _ => return Ok(Vec::new()),
+    };
+    Ok(raw
+        .iter()
+        .filter_map(|a| match a {
+            serde_json::Value::Object(fields) => Some(Answer {
+                answer: text(fields.get("answer"))?,
+                url: text(fields.get("url")),
+                engine: text(fields.get("engine")),
+            }),
+            other => Some(Answer {
+                answer: text(Some(other))?,
+                url: None,
+                engine: None,
+            }),
+        })
+        .collect())
+}
+
+// SearXNG reports unresponsive engines as `[name, error]` pairs; entries
+// without a name are skipped and a missing error is left empty.
+fn deserialize_unresponsive_engines<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Vec<UnresponsiveEngine>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let raw = match Option::<serde_json::Value>::deserialize(deserializer)? {
+        Some(serde_json::Value::Array(items)) => items,
+        _ => return Ok(Vec::new()),
+    };
+    Ok(raw
+        .into_iter()
+        .filter_map(|entry| {
+            let serde_json::Value::Array(pair) = entry else {
+                return None;
+            };
+            let mut pair = pair.into_iter();
+            let engine = lenient_string(pair.next()?).ok().flatten()?;
+            let error = pair
+                .next()
+                .and_then(|error| lenient_string(error).ok().flatten())
+                .unwrap_or_default();
+            Some(UnresponsiveEngine { engine, error })
+        })
+        .collect())
+}
+
+fn is_tracking_param(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    name.starts_with("utm_")
+        || matches!(
+            name.as_str(),
+            "fbclid"
+                | "gclid"
+                | "dclid"
+                | "msclkid"
+                | "yclid"
+                | "igshid"
+                | "mc_cid"
+                | "mc_eid"
+                | "_ga"
+                | "_hsenc"
+                | "_hsmi"
+                | "ref_src"
+        )
+}
+
+/// Key used to de-duplicate results: scheme/host case, default port, fragment,
+/// trailing slash and tracking parameters do not make two URLs different.
+pub fn normalize_result_url(raw: &str) -> String {
+    let Ok(mut url) = Url::parse(raw.trim()) else {
+        return raw.trim().to_string();
+    };
+
+    url.set_fragment(None);
+
+    let kept: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(k, _)| !is_tracking_param(k))
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    if kept.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(kept);
+    }
+
+    let path = url.path().to_string();
+    if path.len() > 1 && path.ends_with('/') {
+        url.set_path(path.trim_end_matches('/'));
+    }
+
+    // `Url` already lowercases scheme and host and drops default ports.
+    let mut out = url.to_string();
+    if url.path() == "/" && url.query().is_none() {
+        out.truncate(out.trim_end_matches('/').len());
+    }
+    out
+}
+
+/// SearXNG answers `/autocompleter` with an OpenSearch `[query, [suggestions]]`
+/// pair, or with a bare list when the request looks like it came from its own UI.
+fn parse_autocomplete(value: &serde_json::Value) -> Result<Vec<String>> {
+    let Some(items) = value.as_array() else {
+        return Err(anyhow!("unexpected /autocompleter response: not an array"));
+    };
+    let list = match items.as_slice() {
+        [serde_json::Value::String(_), serde_json::Value::Array(list)] => list,
+        _ => items,
+    };
+    Ok(list
+        .iter()
+        .filter_map(|v| v.as_str())
+        .map(str::to_string)
+        .collect())
 }
 
 #[derive(Debug, Default, Clone)]
@@ -139,16 +811,189 @@ pub struct SearchParams {
     pub time_range: Option<String>,
     pub safe_search: Option<SafeSearch>,
     pub num_results: Option<usize>,
+    pub aggregate: Option<bool>,
+}
+
+#[derive(Debug, Default)]
+struct InstanceState {
+    ejected_until: Option<Instant>,
+    consecutive_failures: u32,
+    last_error: Option<String>,
+}
+
+#[derive(Debug)]
+struct Instance {
+    base_url: String,
+    state: Mutex<InstanceState>,
+}
+
+impl Instance {
+    fn new(base_url: String) -> Self {
+        Self {
+            base_url,
+            state: Mutex::new(InstanceState::default()),
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, InstanceState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn record_success(&self) {
+        let mut state = self.state();
+        state.ejected_until = None;
+        state.consecutive_failures = 0;
+        state.last_error = None;
+    }
+
+    /// Note a failed request. Only transient failures (see [`retry_hint`])
+    /// count against the instance and eject it; a 4xx or an unparsable
+    /// response is the request's fault as much as the instance's.
+    fn record_failure(&self, err: &anyhow::Error, eject_for: Duration) {
+        tracing::warn!(instance = %self.base_url, error = %err, "searxng instance failed");
+        let mut state = self.state();
+        state.last_error = Some(format!("{err:#}"));
+        if retry_hint(err).is_none() {
+            return;
+        }
+        state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+        if !eject_for.is_zero() {
+            state.ejected_until = Some(Instant::now() + eject_for);
+        }
+    }
+}
+
+/// Per-instance status reported by the `health` tool.
+#[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
+pub struct InstanceHealth {
+    pub base_url: String,
+    pub ok: bool,
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub ejected: bool,
+    pub consecutive_failures: u32,
+}
+
+/// Non-success HTTP status from a SearXNG endpoint.
+#[derive(Debug)]
+struct StatusError {
+    endpoint: &'static str,
+    status: reqwest::StatusCode,
+    body: String,
+    retry_after: Option<Duration>,
+}
+
+impl std::fmt::Display for StatusError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "searxng {} failed: {}: {}",
+            self.endpoint, self.status, self.body
+        )
+    }
+}
+
+impl std::error::Error for StatusError {}
+
+fn parse_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = httpdate::parse_http_date(value).ok()?;
+    Some(
+        at.duration_since(SystemTime::now())
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+async fn check_status(
+    resp: reqwest::Response,
+    endpoint: &'static str,
+) -> Result<reqwest::Response> {
+    let status = resp.status();
+    if status.is_success() {
+        return Ok(resp);
+    }
+    let retry_after = parse_retry_after(resp.headers());
+    let body = resp.text().await.unwrap_or_default();
+    Err(StatusError {
+        endpoint,
+        status,
+        body,
+        retry_after,
+    }
+    .into())
+}
+
+#[derive(Debug)]
+struct InstancesFailed(Vec<(String, anyhow::Error)>);
+
+impl std::fmt::Display for InstancesFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("all searxng instances failed: ")?;
+        for (i, (base, e)) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{base}: {e:#}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for InstancesFailed {}
+
+fn combine_errors(mut errors: Vec<(String, anyhow::Error)>) -> anyhow::Error {
+    if errors.len() == 1 {
+        return errors.remove(0).1;
+    }
+    InstancesFailed(errors).into()
+}
+
+/// Whether an error is transient: transport failures, 429 and 5xx are; other
+/// statuses and malformed responses are not. The inner option carries any
+/// `Retry-After` delay the server asked for.
+fn retry_hint(err: &anyhow::Error) -> Option<Option<Duration>> {
+    if let Some(failed) = err.downcast_ref::<InstancesFailed>() {
+        return failed
+            .0
+            .iter()
+            .filter_map(|(_, e)| retry_hint(e))
+            .reduce(|a, b| a.max(b));
+    }
+    for cause in err.chain() {
+        if let Some(e) = cause.downcast_ref::<StatusError>() {
+            let transient =
+                e.status == reqwest::StatusCode::TOO_MANY_REQUESTS || e.status.is_server_error();
+            return transient.then_some(e.retry_after);
+        }
+        if let Some(e) = cause.downcast_ref::<reqwest::Error>() {
+            return (e.is_timeout() || e.is_connect() || e.is_request()).then_some(None);
+        }
+    }
+    None
 }
 
 #[derive(Clone, Debug)]
 pub struct SearxngClient {
     cfg: SearxngConfig,
     http: reqwest::Client,
+    instances: Arc<[Instance]>,
+    next: Arc<AtomicUsize>,
+    breaker: Arc<CircuitBreaker>,
 }
 
 impl SearxngClient {
     pub fn new(cfg: SearxngConfig) -> Result<Self> {
+        if cfg.base_urls.is_empty() {
+            return Err(anyhow!("SEARXNG_BASE_URL must name at least one instance"));
+        }
+        for base in &cfg.base_urls {
+            Url::parse(base).with_context(|| format!("invalid SEARXNG_BASE_URL '{base}'"))?;
+        }
+
         let mut headers = HeaderMap::new();
         headers.insert(
             USER_AGENT,
@@ -161,38 +1006,227 @@ impl SearxngClient {
             .build()
             .context("failed to build HTTP client")?;
 
-        Ok(Self { cfg, http })
+        let instances = cfg
+            .base_urls
+            .iter()
+            .map(|base| Instance::new(base.trim_end_matches('/').to_string()))
+            .collect();
+
+        let breaker = Arc::new(CircuitBreaker::new(
+            cfg.breaker_threshold,
+            cfg.breaker_cooldown,
+        ));
+
+        Ok(Self {
+            cfg,
+            http,
+            instances,
+            next: Arc::new(AtomicUsize::new(0)),
+            breaker,
+        })
+    }
+
+    pub fn config(&self) -> &SearxngConfig {
+        &self.cfg
+    }
+
+    /// Cache key for a search: the request URL with defaults applied, plus the
+    /// client-side options that shape the response.
+    pub fn cache_key(&self, params: &SearchParams) -> Result<String> {
+        fn normalize_csv(v: &str) -> String {
+            let mut parts: Vec<String> = parse_csv(&v.to_ascii_lowercase());
+            parts.sort();
+            parts.dedup();
+            parts.join(",")
+        }
+
+        let mut normalized = params.clone();
+        normalized.query = params
+            .query
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        normalized.categories = params.categories.as_deref().map(normalize_csv);
+        normalized.engines = params.engines.as_deref().map(normalize_csv);
+        normalized.language = params.language.as_deref().map(|v| v.trim().to_string());
+        normalized.time_range = params.time_range.as_deref().map(|v| v.trim().to_string());
+
+        let url = self.search_url(
+            &self.instances[0].base_url,
+            &normalized,
+            Some(params.pageno.unwrap_or(1).max(1)),
+        )?;
+        let limit = params.num_results.unwrap_or(self.cfg.num_results);
+        let aggregate = params.aggregate.unwrap_or(self.cfg.aggregate_pages);
+        Ok(format!("{url} num_results={limit} aggregate={aggregate}"))
+    }
+
+    pub fn circuit_state(&self) -> CircuitState {
+        self.breaker.state()
+    }
+
+    /// Retry `op` on transient errors according to the configured policy.
+    async fn retrying<T>(&self, op: impl AsyncFn() -> Result<T>) -> Result<T> {
+        let mut attempt = 1;
+        loop {
+            let err = match op().await {
+                Ok(v) => return Ok(v),
+                Err(e) => e,
+            };
+            let Some(retry_after) = retry_hint(&err) else {
+                return Err(err);
+            };
+            let Some(delay) = self.cfg.retry.delay(attempt, retry_after) else {
+                return Err(err);
+            };
+            tracing::debug!(
+                attempt,
+                delay_ms = delay.as_millis(),
+                error = %err,
+                "retrying searxng request"
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
     }
 
-    pub async fn test_connection(&self) -> Result<()> {
-        let url = format!("{}/config", self.cfg.base_url.trim_end_matches('/'));
+    /// Run `op` with retries, behind the circuit breaker. Only transient
+    /// failures count against the breaker; any other response means SearXNG is up.
+    async fn guarded<T>(&self, op: impl AsyncFn() -> Result<T>) -> Result<T> {
+        self.breaker.check("searxng")?;
+        let result = self.retrying(op).await;
+        match &result {
+            Err(e) if retry_hint(e).is_some() => self.breaker.record_failure(),
+            _ => self.breaker.record_success(),
+        }
+        result
+    }
+
+    pub async fn test_connection(&self, base: &str) -> Result<()> {
+        self.retrying(async || self.test_connection_once(base).await)
+            .await
+    }
+
+    async fn test_connection_once(&self, base: &str) -> Result<()> {
+        let url = format!("{base}/config");
         let resp = self
             .http
             .get(url)
             .send()
             .await
             .context("config request failed")?;
-        let status = resp.status();
-        if !status.is_success() {
-            let body = resp.text().await.unwrap_or_default();
-            return Err(anyhow!("searxng /config failed: {}: {}", status, body));
-        }
+        check_status(resp, "/config").await?;
         Ok(())
     }
 
-    pub async fn search(&self, params: SearchParams) -> Result<SearxngResponse> {
-        let base = self.cfg.base_url.trim_end_matches('/');
+    /// Instances to try for the next request, in order. Instances whose ejection
+    /// window has elapsed are re-admitted only after a successful `/config` probe.
+    async fn candidates(&self) -> Vec<&Instance> {
+        let n = self.instances.len();
+        let start = match self.cfg.strategy {
+            InstanceStrategy::RoundRobin => self.next.fetch_add(1, Ordering::Relaxed) % n,
+            InstanceStrategy::Failover | InstanceStrategy::FanOut => 0,
+        };
+        let ordered: Vec<&Instance> = (0..n).map(|i| &self.instances[(start + i) % n]).collect();
+
+        let now = Instant::now();
+        let mut ready = Vec::new();
+        let mut ejected = Vec::new();
+        let mut due = Vec::new();
+        for (pos, instance) in ordered.iter().enumerate() {
+            match instance.state().ejected_until {
+                None => ready.push((pos, *instance)),
+                Some(until) if until <= now => due.push((pos, *instance)),
+                Some(_) => ejected.push(*instance),
+            }
+        }
+
+        let probes = join_all(due.iter().map(|(_, i)| self.probe(i))).await;
+        for ((pos, instance), ok) in due.into_iter().zip(probes) {
+            if ok {
+                ready.push((pos, instance));
+            } else {
+                ejected.push(instance);
+            }
+        }
+
+        if ready.is_empty() {
+            // Everything is ejected; trying anyway beats failing without a request.
+            return ordered;
+        }
+        ready.sort_by_key(|(pos, _)| *pos);
+        ready.into_iter().map(|(_, i)| i).collect()
+    }
+
+    async fn probe(&self, instance: &Instance) -> bool {
+        match self.test_connection_once(&instance.base_url).await {
+            Ok(()) => {
+                instance.record_success();
+                true
+            }
+            Err(e) => {
+                instance.record_failure(&e, self.cfg.eject_for);
+                false
+            }
+        }
+    }
+
+    async fn with_failover<T>(&self, op: impl AsyncFn(&str) -> Result<T>) -> Result<T> {
+        let mut errors = Vec::new();
+        for instance in self.candidates().await {
+            match op(&instance.base_url).await {
+                Ok(v) => {
+                    instance.record_success();
+                    return Ok(v);
+                }
+                Err(e) => {
+                    instance.record_failure(&e, self.cfg.eject_for);
+                    // The instance answered; another would reject the request too.
+                    if retry_hint(&e).is_none() {
+                        return Err(e);
+                    }
+                    errors.push((instance.base_url.clone(), e));
+                }
+            }
+        }
+        Err(combine_errors(errors))
+    }
+
+    /// Probe every instance with `/config` and report its status.
+    pub async fn instance_health(&self) -> Vec<InstanceHealth> {
+        join_all(self.instances.iter().map(|instance| async move {
+            let started = Instant::now();
+            let result = self.test_connection(&instance.base_url).await;
+            let latency_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+            match &result {
+                Ok(()) => instance.record_success(),
+                Err(e) => instance.record_failure(e, self.cfg.eject_for),
+            }
+            let state = instance.state();
+            InstanceHealth {
+                base_url: instance.base_url.clone(),
+                ok: result.is_ok(),
+                latency_ms,
+                error: state.last_error.clone(),
+                ejected: state.ejected_until.is_some_and(|t| t > Instant::now()),
+                consecutive_failures: state.consecutive_failures,
+            }
+        }))
+        .await
+    }
+
+    fn search_url(&self, base: &str, params: &SearchParams, pageno: Option<u32>) -> Result<Url> {
         let mut url = Url::parse(&format!("{base}/search")).context("invalid SEARXNG_BASE_URL")?;
 
-        let lang = params.language.unwrap_or_else(|| self.cfg.language.clone());
-        let engines = params.engines.or_else(|| {
+        let lang = params.language.as_deref().unwrap_or(&self.cfg.language);
+        let engines = params.engines.clone().or_else(|| {
             if self.cfg.default_engines.is_empty() {
                 None
             } else {
                 Some(self.cfg.default_engines.join(","))
             }
         });
-        let categories = params.categories.or_else(|| {
+        let categories = params.categories.clone().or_else(|| {
             if self.cfg.default_categories.is_empty() {
                 None
             } else {
@@ -205,7 +1239,7 @@ impl SearxngClient {
             let mut qp = url.query_pairs_mut();
             qp.append_pair("q", &params.query);
             qp.append_pair("format", "json");
-            qp.append_pair("language", &lang);
+            qp.append_pair("language", lang);
             qp.append_pair("safesearch", &(safe_search as u8).to_string());
             if let Some(v) = categories.as_deref() {
                 qp.append_pair("categories", v);
@@ -213,7 +1247,7 @@ impl SearxngClient {
             if let Some(v) = engines.as_deref() {
                 qp.append_pair("engines", v);
             }
-            if let Some(v) = params.pageno {
+            if let Some(v) = pageno {
                 qp.append_pair("pageno", &v.to_string());
             }
             if let Some(v) = params.time_range.as_deref() {
@@ -221,50 +1255,193 @@ impl SearxngClient {
             }
         }
 
+        Ok(url)
+    }
+
+    async fn fetch_page(
+        &self,
+        params: &SearchParams,
+        pageno: Option<u32>,
+    ) -> Result<SearxngResponse> {
+        self.guarded(async || self.fetch_page_once(params, pageno).await)
+            .await
+    }
+
+    async fn fetch_page_once(
+        &self,
+        params: &SearchParams,
+        pageno: Option<u32>,
+    ) -> Result<SearxngResponse> {
+        if self.cfg.strategy != InstanceStrategy::FanOut || self.instances.len() == 1 {
+            return self
+                .with_failover(async |base| self.fetch_page_from(base, params, pageno).await)
+                .await;
+        }
+
+        let candidates = self.candidates().await;
+        let pages = join_all(
+            candidates
+                .iter()
+                .map(|i| self.fetch_page_from(&i.base_url, params, pageno)),
+        )
+        .await;
+
+        let mut merged: Option<SearxngResponse> = None;
+        let mut errors = Vec::new();
+        for (instance, page) in candidates.into_iter().zip(pages) {
+            match page {
+                Ok(page) => {
+                    instance.record_success();
+                    match merged.as_mut() {
+                        Some(m) => m.merge_page(page),
+                        None => merged = Some(page),
+                    }
+                }
+                Err(e) => {
+                    instance.record_failure(&e, self.cfg.eject_for);
+                    errors.push((instance.base_url.clone(), e));
+                }
+            }
+        }
+        merged.ok_or_else(|| combine_errors(errors))
+    }
+
+    async fn fetch_page_from(
+        &self,
+        base: &str,
+        params: &SearchParams,
+        pageno: Option<u32>,
+    ) -> Result<SearxngResponse> {
+        let url = self.search_url(base, params, pageno)?;
         let resp = self
             .http
             .get(url)
             .send()
             .await
             .context("search request failed")?;
-        let status = resp.status();
-        if !status.is_success() {
-            let body = resp.text().await.unwrap_or_default();
-            return Err(anyhow!("searxng /search failed: {}: {}", status, body));
-        }
+        let resp = check_status(resp, "/search").await?;
 
         let mut parsed: SearxngResponse = resp.json().await.context("failed to parse JSON")?;
+        parsed.dedupe_results();
+        Ok(parsed)
+    }
+
+    pub async fn search(&self, params: SearchParams) -> Result<SearxngResponse> {
+        let limit = params.num_results.unwrap_or(self.cfg.num_results);
+        let aggregate = params.aggregate.unwrap_or(self.cfg.aggregate_pages);
+
+        let mut merged = self.fetch_page(&params, params.pageno).await?;
+
+        if aggregate && limit > 0 {
+            let page_size = merged.results.len();
+            let first = params.pageno.unwrap_or(1).max(1);
+            let last = first.saturating_add(self.cfg.max_pages.max(1) - 1);
+            let mut next = first.saturating_add(1);
+
+            // Fetch as many pages as should cover the shortfall in one concurrent batch,
+            // then top up in further batches if de-duplication ate into the count.
+            while page_size > 0 && merged.results.len() < limit && next <= last {
+                let missing = limit - merged.results.len();
+                let wanted = u32::try_from(missing.div_ceil(page_size)).unwrap_or(u32::MAX);
+                let batch_end = next.saturating_add(wanted - 1).min(last);
+
+                let pages = join_all(
+                    (next..=batch_end).map(|pageno| self.fetch_page(&params, Some(pageno))),
+                )
+                .await;
+
+                let mut exhausted = false;
+                for (pageno, page) in (next..=batch_end).zip(pages) {
+                    match page {
+                        Ok(page) => {
+                            exhausted |= page.results.is_empty();
+                            merged.merge_page(page);
+                        }
+                        Err(e) => {
+                            tracing::warn!(pageno, error = %e, "searxng page fetch failed");
+                            exhausted = true;
+                        }
+                    }
+                }
+                if exhausted {
+                    break;
+                }
+                next = batch_end.saturating_add(1);
+            }
+        }
 
-        parsed.results.sort_by(|a, b| {
+        merged.results.sort_by(|a, b| {
             b.score
                 .partial_cmp(&a.score)
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
 
-        let limit = params.num_results.unwrap_or(self.cfg.num_results);
-        if limit > 0 && parsed.results.len() > limit {
-            parsed.results.truncate(limit);
+        if limit > 0 && merged.results.len() > limit {
+            merged.results.truncate(limit);
         }
 
-        Ok(parsed)
+        Ok(merged)
+    }
+
+    pub async fn autocomplete(&self, query: &str, language: Option<&str>) -> Result<Vec<String>> {
+        let language = language.unwrap_or(&self.cfg.language);
+        self.guarded(async || {
+            self.with_failover(async |base| self.autocomplete_from(base, query, language).await)
+                .await
+        })
+        .await
+    }
+
+    async fn autocomplete_from(
+        &self,
+        base: &str,
+        query: &str,
+        language: &str,
+    ) -> Result<Vec<String>> {
+        let mut url =
+            Url::parse(&format!("{base}/autocompleter")).context("invalid SEARXNG_BASE_URL")?;
+        url.query_pairs_mut()
+            .append_pair("q", query)
+            .append_pair("language", language);
+
+        let resp = self
+            .http
+            .get(url)
+            .send()
+            .await
+            .context("autocompleter request failed")?;
+        let resp = check_status(resp, "/autocompleter").await?;
+        let value: serde_json::Value = resp
+            .json()
+            .await
+            .context("failed to parse autocompleter JSON")?;
+        parse_autocomplete(&value)
     }
 
     pub async fn get_engines(
         &self,
         filter: EngineFilter,
     ) -> Result<HashMap<String, serde_json::Value>> {
-        let url = format!("{}/config", self.cfg.base_url.trim_end_matches('/'));
+        self.guarded(async || {
+            self.with_failover(async |base| self.get_engines_from(base, filter).await)
+                .await
+        })
+        .await
+    }
+
+    async fn get_engines_from(
+        &self,
+        base: &str,
+        filter: EngineFilter,
+    ) -> Result<HashMap<String, serde_json::Value>> {
+        let url = format!("{base}/config");
         let resp = self
             .http
             .get(url)
             .send()
             .await
             .context("config request failed")?;
-        let status = resp.status();
-        if !status.is_success() {
-            let body = resp.text().await.unwrap_or_default();
-            return Err(anyhow!("searxng /config failed: {}: {}", status, body));
-        }
+        let resp = check_status(resp, "/config").await?;
         let cfg: serde_json::Value = resp.json().await.context("failed to parse config JSON")?;
 
         let engines = cfg
@@ -295,3 +1472,447 @@ impl SearxngClient {
         Ok(out)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_extras_from_searxng_json() {
+        let json = r#"{
+            "query": "rust",
+            "number_of_results": 1200,
+            "results": [{"title": "Rust", "url": "https://www.rust-lang.org/"}],
+            "answers": ["plain answer", {"answer": "rich answer", "url": "https://example.com/", "engine": "wikidata"}],
+            "corrections": ["rust lang"],
+            "infoboxes": [{
+                "infobox": "Rust",
+                "id": "https://en.wikipedia.org/wiki/Rust_(programming_language)",
+                "content": "General-purpose programming language",
+                "urls": [{"title": "Official website", "url": "https://www.rust-lang.org/"}],
+                "attributes": [{"label": "Designed by", "value": "Graydon Hoare"}],
+                "engine": "wikipedia"
+            }],
+            "suggestions": [],
+            "unresponsive_engines": [["google", "timeout"]]
+        }"#;
+
+        let resp: SearxngResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(resp.number_of_results, Some(1200));
+        assert_eq!(resp.answers.len(), 2);
+        assert_eq!(resp.answers[0].answer, "plain answer");
+        assert_eq!(resp.answers[1].engine.as_deref(), Some("wikidata"));
+        assert_eq!(resp.corrections, vec!["rust lang"]);
+        assert_eq!(resp.infoboxes[0].attributes[0].value, "Graydon Hoare");
+        assert_eq!(resp.unresponsive_engines[0].engine, "google");
+        assert_eq!(resp.unresponsive_engines[0].error, "timeout");
+    }
+
+    #[test]
+    fn tolerates_unexpected_extra_shapes() {
+        let json = r#"{
+            "results": [{"title": "Rust", "url": "https://www.rust-lang.org/"}],
+            "answers": [
+                "plain answer",
+                {"answer": "rich answer", "url": null, "engine": "wikidata"},
+                {"template": "answer/translations.html", "translations": [{"text": "rouille"}]},
+                {"answer": null},
+                42,
+                null
+            ],
+            "infoboxes": [
+                {
+                    "infobox": "Rust",
+                    "id": null,
+                    "content": null,
+                    "img_src": null,
+                    "urls": [{"title": null, "url": "https://www.rust-lang.org/"}, {"title": "broken"}],
+                    "attributes": [{"label": "First appeared", "value": 2015}, {"label": "Typing", "value": null}]
+                },
+                {"content": "no title"},
+                "garbage"
+            ]
+        }"#;
+
+        let resp: SearxngResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(resp.results.len(), 1);
+        let answers: Vec<_> = resp.answers.iter().map(|a| a.answer.as_str()).collect();
+        assert_eq!(answers, vec!["plain answer", "rich answer", "42"]);
+        assert_eq!(resp.answers[1].url, None);
+
+        assert_eq!(resp.infoboxes.len(), 1);
+        let infobox = &resp.infoboxes[0];
+        assert_eq!(infobox.content, "");
+        assert_eq!(infobox.id, None);
+        assert_eq!(infobox.urls.len(), 1);
+        assert_eq!(infobox.urls[0].title, "");
+        assert_eq!(infobox.attributes[0].value, "2015");
+        assert_eq!(infobox.attributes[1].value, "");
+
+        let json = r#"{
+            "results": [],
+            "unresponsive_engines": [
+                ["google", "timeout"],
+                ["bing", null],
+                ["brave"],
+                [null, "CAPTCHA"],
+                "qwant",
+                {"engine": "ddg"},
+                ["startpage", 429]
+            ]
+        }"#;
+        let resp: SearxngResponse = serde_json::from_str(json).unwrap();
+        let engines: Vec<_> = resp
+            .unresponsive_engines
+            .iter()
+            .map(|e| (e.engine.as_str(), e.error.as_str()))
+            .collect();
+        assert_eq!(
+            engines,
+            vec![
+                ("google", "timeout"),
+                ("bing", ""),
+                ("brave", ""),
+                ("startpage", "429")
+            ]
+        );
+        let resp: SearxngResponse =
+            serde_json::from_str(r#"{"unresponsive_engines": null}"#).unwrap();
+        assert!(resp.unresponsive_engines.is_empty());
+    }
+
+    #[test]
+    fn parses_result_templates() {
+        let json = r#"{"results": [
+            {"title": "Ferris", "url": "https://example.com/ferris", "template": "images.html",
+             "img_src": "https://example.com/ferris.png", "thumbnail_src": "https://example.com/t.png",
+             "resolution": "800 x 600"},
+            {"title": "Talk", "url": "https://example.com/talk", "template": "videos.html",
+             "length": "12:34", "duration": 754, "iframe_src": "https://example.com/embed", "publishedDate": "2024-05-01T00:00:00"},
+            {"title": "Berlin", "url": "https://example.com/berlin", "template": "map.html",
+             "latitude": "52.52", "longitude": 13.405},
+            {"title": "Paper", "url": "https://example.com/paper", "template": "paper.html",
+             "doi": "10.1000/xyz", "authors": ["A. Author", ""], "journal": "J. Rust"},
+            {"title": "Plain", "url": "https://example.com/", "template": "default.html", "img_src": ""},
+            {"title": "Clip", "url": "https://example.com/clip", "template": "videos.html",
+             "length": null, "duration": "0:42"}
+        ]}"#;
+
+        let resp: SearxngResponse = serde_json::from_str(json).unwrap();
+        let details: Vec<_> = resp.results.iter().map(|r| r.details.clone()).collect();
+        match &details[0] {
+            Some(ResultDetails::Image(img)) => {
+                assert_eq!(img.img_src, "https://example.com/ferris.png");
+                assert_eq!(img.resolution.as_deref(), Some("800 x 600"));
+            }
+            other => panic!("expected image, got {other:?}"),
+        }
+        match &details[1] {
+            Some(ResultDetails::Video(video)) => {
+                assert_eq!(video.duration.as_deref(), Some("12:34"))
+            }
+            other => panic!("expected video, got {other:?}"),
+        }
+        assert_eq!(
+            resp.results[1].published_date.as_deref(),
+            Some("2024-05-01T00:00:00")
+        );
+        match &details[2] {
+            Some(ResultDetails::Map(map)) => {
+                assert_eq!(map.latitude, Some(52.52));
+                assert_eq!(map.longitude, Some(13.405));
+            }
+            other => panic!("expected map, got {other:?}"),
+        }
+        match &details[3] {
+            Some(ResultDetails::Paper(paper)) => {
+                assert_eq!(paper.doi.as_deref(), Some("10.1000/xyz"));
+                assert_eq!(paper.authors, vec!["A. Author"]);
+            }
+            other => panic!("expected paper, got {other:?}"),
+        }
+        assert!(details[4].is_none());
+        match &details[5] {
+            Some(ResultDetails::Video(video)) => {
+                assert_eq!(video.duration.as_deref(), Some("0:42"))
+            }
+            other => panic!("expected video, got {other:?}"),
+        }
+
+        let out = serde_json::to_value(&resp.results[0]).unwrap();
+        assert_eq!(out["details"]["type"], "image");
+        assert!(
+            serde_json::to_value(&resp.results[4])
+                .unwrap()
+                .get("details")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn normalizes_urls_for_dedup() {
+        let base = normalize_result_url("https://example.com/docs");
+        for variant in [
+            "HTTPS://Example.COM/docs/",
+            "https://example.com:443/docs#intro",
+            "https://example.com/docs?utm_source=x&fbclid=y",
+        ] {
+            assert_eq!(normalize_result_url(variant), base, "{variant}");
+        }
+        assert_eq!(
+            normalize_result_url("https://example.com/"),
+            normalize_result_url("https://EXAMPLE.com")
+        );
+        assert_eq!(
+            normalize_result_url("https://example.com/s?q=rust&utm_medium=a"),
+            "https://example.com/s?q=rust"
+        );
+        assert_ne!(
+            normalize_result_url("https://example.com/a"),
+            normalize_result_url("https://example.com/b")
+        );
+        assert_ne!(
+            normalize_result_url("http://example.com/a"),
+            normalize_result_url("https://example.com/a")
+        );
+    }
+
+    #[test]
+    fn merge_page_dedupes_and_merges_engines() {
+        let mut first: SearxngResponse = serde_json::from_str(
+            r#"{"results": [
+                {"title": "A", "url": "https://a.example/", "score": 1.0, "engines": ["google"]},
+                {"title": "A again", "url": "https://A.example", "score": 2.0, "engines": ["bing"]}
+            ], "suggestions": ["one"]}"#,
+        )
+        .unwrap();
+        first.dedupe_results();
+        assert_eq!(first.results.len(), 1);
+        assert_eq!(first.results[0].score, 2.0);
+        assert_eq!(first.results[0].engines, vec!["google", "bing"]);
+
+        let second: SearxngResponse = serde_json::from_str(
+            r#"{"results": [
+                {"title": "A", "url": "https://a.example/?utm_source=x", "engines": ["ddg"]},
+                {"title": "B", "url": "https://b.example/"}
+            ], "suggestions": ["one", "two"]}"#,
+        )
+        .unwrap();
+        first.merge_page(second);
+        assert_eq!(first.results.len(), 2);
+        assert_eq!(first.results[0].engines, vec!["google", "bing", "ddg"]);
+        assert_eq!(first.suggestions, vec!["one", "two"]);
+    }
+
+    #[test]
+    fn cache_key_normalizes_params() {
+        let client = SearxngClient::new(SearxngConfig::default()).unwrap();
+        let a = SearchParams {
+            query: "  rust   async ".to_string(),
+            engines: Some("Bing, google".to_string()),
+            ..Default::default()
+        };
+        let b = SearchParams {
+            query: "rust async".to_string(),
+            engines: Some("google,bing".to_string()),
+            pageno: Some(1),
+            num_results: Some(DEFAULT_NUM_RESULTS),
+            ..Default::default()
+        };
+        assert_eq!(client.cache_key(&a).unwrap(), client.cache_key(&b).unwrap());
+
+        let c = SearchParams {
+            pageno: Some(2),
+            ..b.clone()
+        };
+        assert_ne!(client.cache_key(&b).unwrap(), client.cache_key(&c).unwrap());
+    }
+
+    #[test]
+    fn parses_instance_strategy() {
+        assert_eq!(
+            InstanceStrategy::parse("").unwrap(),
+            InstanceStrategy::Failover
+        );
+        assert_eq!(
+            InstanceStrategy::parse("Round-Robin").unwrap(),
+            InstanceStrategy::RoundRobin
+        );
+        assert_eq!(
+            InstanceStrategy::parse("fanout").unwrap(),
+            InstanceStrategy::FanOut
+        );
+        assert!(InstanceStrategy::parse("random").is_err());
+    }
+
+    #[tokio::test]
+    async fn round_robin_rotates_and_skips_ejected_instances() {
+        let cfg = SearxngConfig {
+            base_urls: vec![
+                "http://a.invalid".to_string(),
+                "http://b.invalid".to_string(),
+                "http://c.invalid".to_string(),
+            ],
+            strategy: InstanceStrategy::RoundRobin,
+            ..Default::default()
+        };
+        let client = SearxngClient::new(cfg).unwrap();
+        let order = |c: Vec<&Instance>| c.iter().map(|i| i.base_url.clone()).collect::<Vec<_>>();
+
+        assert_eq!(
+            order(client.candidates().await),
+            vec!["http://a.invalid", "http://b.invalid", "http://c.invalid"]
+        );
+        assert_eq!(
+            order(client.candidates().await),
+            vec!["http://b.invalid", "http://c.invalid", "http://a.invalid"]
+        );
+
+        let unavailable = || -> anyhow::Error {
+            StatusError {
+                endpoint: "/search",
+                status: reqwest::StatusCode::SERVICE_UNAVAILABLE,
+                body: String::new(),
+                retry_after: None,
+            }
+            .into()
+        };
+        client.instances[2].record_failure(&unavailable(), Duration::from_secs(60));
+        assert_eq!(
+            order(client.candidates().await),
+            vec!["http://a.invalid", "http://b.invalid"]
+        );
+
+        for instance in client.instances.iter() {
+            instance.record_failure(&unavailable(), Duration::from_secs(60));
+        }
+        assert_eq!(client.candidates().await.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn bad_request_neither_ejects_nor_fails_over() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Answers every request with `status` and counts them.
+        async fn serve(status: &'static str) -> (String, Arc<AtomicUsize>) {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let base = format!("http://{}", listener.local_addr().unwrap());
+            let hits = Arc::new(AtomicUsize::new(0));
+            let counter = hits.clone();
+            tokio::spawn(async move {
+                while let Ok((mut conn, _)) = listener.accept().await {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    let mut buf = [0u8; 4096];
+                    let _ = conn.read(&mut buf).await;
+                    let response = format!(
+                        "HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                    );
+                    let _ = conn.write_all(response.as_bytes()).await;
+                }
+            });
+            (base, hits)
+        }
+
+        let (bad, bad_hits) = serve("400 Bad Request").await;
+        let (other, other_hits) = serve("200 OK").await;
+        let client = SearxngClient::new(SearxngConfig {
+            base_urls: vec![bad, other],
+            eject_for: Duration::from_secs(60),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let err = client
+            .search(SearchParams {
+                query: "rust".to_string(),
+                engines: Some("no-such-engine".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("400"), "{err:#}");
+        assert_eq!(bad_hits.load(Ordering::SeqCst), 1);
+        assert_eq!(other_hits.load(Ordering::SeqCst), 0);
+        assert!(
+            client
+                .instances
+                .iter()
+                .all(|i| i.state().ejected_until.is_none())
+        );
+        assert_eq!(client.circuit_state(), CircuitState::Closed);
+    }
+
+    #[test]
+    fn classifies_transient_errors() {
+        let status = |code: u16, retry_after: Option<Duration>| -> anyhow::Error {
+            StatusError {
+                endpoint: "/search",
+                status: reqwest::StatusCode::from_u16(code).unwrap(),
+                body: String::new(),
+                retry_after,
+            }
+            .into()
+        };
+
+        assert_eq!(
+            retry_hint(&status(429, Some(Duration::from_secs(2)))),
+            Some(Some(Duration::from_secs(2)))
+        );
+        assert_eq!(retry_hint(&status(503, None)), Some(None));
+        assert_eq!(retry_hint(&status(403, None)), None);
+        assert_eq!(retry_hint(&anyhow!("failed to parse JSON")), None);
+
+        let combined = combine_errors(vec![
+            ("http://a".to_string(), status(403, None)),
+            ("http://b".to_string(), status(502, None)),
+        ]);
+        assert_eq!(retry_hint(&combined), Some(None));
+        assert!(
+            combined
+                .to_string()
+                .starts_with("all searxng instances failed")
+        );
+    }
+
+    #[test]
+    fn parses_retry_after_header() {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("7"));
+        assert_eq!(parse_retry_after(&headers), Some(Duration::from_secs(7)));
+
+        headers.insert(
+            RETRY_AFTER,
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+        assert_eq!(parse_retry_after(&headers), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn parses_autocomplete_shapes() {
+        let opensearch = serde_json::json!(["rus", ["rust", "rust lang"]]);
+        assert_eq!(
+            parse_autocomplete(&opensearch).unwrap(),
+            vec!["rust", "rust lang"]
+        );
+
+        let bare = serde_json::json!(["rust", "rustup"]);
+        assert_eq!(parse_autocomplete(&bare).unwrap(), vec!["rust", "rustup"]);
+
+        assert!(parse_autocomplete(&serde_json::json!({"q": "x"})).is_err());
+    }
+
+    #[test]
+    fn strip_extras_omits_fields_from_output() {
+        let json = r#"{"results": [], "answers": ["42"], "unresponsive_engines": [["bing", "captcha"]], "number_of_results": 3}"#;
+        let mut resp: SearxngResponse = serde_json::from_str(json).unwrap();
+        resp.strip_extras();
+
+        let out = serde_json::to_value(&resp).unwrap();
+        let obj = out.as_object().unwrap();
+        assert!(obj.contains_key("results"));
+        assert!(obj.contains_key("suggestions"));
+        assert!(!obj.contains_key("answers"));
+        assert!(!obj.contains_key("unresponsive_engines"));
+        assert!(!obj.contains_key("number_of_results"));
+    }
+}
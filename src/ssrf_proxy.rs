@@ -0,0 +1,255 @@
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use anyhow::{Context, Result, anyhow};
+use reqwest::Url;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+use crate::resolver::PinnedResolver;
+
+const MAX_HEAD_BYTES: usize = 16 * 1024;
+
+/// Hop-by-hop headers that must not be forwarded upstream.
+const HOP_BY_HOP: &[&str] = &[
+    "connection",
+    "proxy-connection",
+    "keep-alive",
+    "proxy-authorization",
+];
+
+/// A loopback HTTP proxy that only connects to vetted addresses.
+///
+/// Obscura resolves and connects on its own, so the pinned resolver cannot be
+/// handed to it directly; instead its traffic is routed through this proxy.
+/// `CONNECT` tunnels (HTTPS) and absolute-form requests (plain HTTP) are both
+/// resolved here, and denied addresses are refused with a 403.
+pub struct SsrfProxy {
+    addr: SocketAddr,
+    accept: JoinHandle<()>,
+}
+
+impl SsrfProxy {
+    pub async fn start(resolver: PinnedResolver) -> Result<Self> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+            .await
+            .context("failed to bind SSRF proxy")?;
+        let addr = listener.local_addr()?;
+        let accept = tokio::spawn(async move {
+            loop {
+                let conn = match listener.accept().await {
+                    Ok((conn, _)) => conn,
+                    Err(e) => {
+                        tracing::debug!(error = %e, "SSRF proxy accept failed");
+                        continue;
+                    }
+                };
+                let resolver = resolver.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = handle(conn, &resolver).await {
+                        tracing::debug!(error = %e, "SSRF proxy connection failed");
+                    }
+                });
+            }
+        });
+        Ok(Self { addr, accept })
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+}
+
+impl Drop for SsrfProxy {
+    fn drop(&mut self) {
+        self.accept.abort();
+    }
+}
+
+/// Read up to the blank line ending the request head; returns the head and
+/// whatever was read past it.
+async fn read_head(conn: &mut TcpStream) -> Result<(String, Vec<u8>)> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    loop {
+        if let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            let rest = buf.split_off(end + 4);
+            buf.truncate(end);
+            let head = String::from_utf8(buf).context("request head is not UTF-8")?;
+            return Ok((head, rest));
+        }
+        if buf.len() > MAX_HEAD_BYTES {
+            return Err(anyhow!("request head exceeds {MAX_HEAD_BYTES} bytes"));
+        }
+        let n = conn.read(&mut chunk).await?;
+        if n == 0 {
+            return Err(anyhow!("connection closed before the request head ended"));
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+}
+
+/// `example.com:443` or `[::1]:443`.
+fn split_host_port(authority: &str) -> Option<(&str, u16)> {
+    let (host, port) = authority.rsplit_once(':')?;
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    Some((host, port.parse().ok()?))
+}
+
+async fn connect_vetted(resolver: &PinnedResolver, host: &str, port: u16) -> io::Result<TcpStream> {
+    let addrs = match host.parse::<IpAddr>() {
+        Ok(ip) => match resolver.denied_by(ip) {
+            None => vec![ip],
+            Some(net) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    format!("refusing to connect to {ip}: it is in denied range {net}"),
+                ));
+            }
+        },
+        Err(_) => resolver.resolve(host).await?,
+    };
+    let mut last_err = None;
+    for ip in addrs {
+        match TcpStream::connect((ip, port)).await {
+            Ok(stream) => return Ok(stream),
+            Err(e) => last_err = Some(e),
+        }
+    }
+    Err(last_err.unwrap_or_else(|| io::Error::other("no addresses to connect to")))
+}
+
+/// Turn an absolute-form proxy request head into an origin-form one for `url`,
+/// asking the origin to close the connection after this request so the next
+/// one comes back through the proxy.
+fn origin_form_head(head: &str, method: &str, url: &Url, version: &str) -> String {
+    let mut target = url.path().to_string();
+    if let Some(query) = url.query() {
+        target.push('?');
+        target.push_str(query);
+    }
+    let mut out = format!("{method} {target} {version}\r\n");
+    for line in head.split("\r\n").skip(1) {
+        let name = line.split(':').next().unwrap_or_default().trim();
+        if line.is_empty() || HOP_BY_HOP.iter().any(|h| name.eq_ignore_ascii_case(h)) {
+            continue;
+        }
+        out.push_str(line);
+        out.push_str("\r\n");
+    }
+    out.push_str("Connection: close\r\n\r\n");
+    out
+}
+
+async fn refuse(conn: &mut TcpStream, reason: &str) -> Result<()> {
+    let response = format!(
+        "HTTP/1.1 403 Forbidden\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{reason}",
+        reason.len()
+    );
+    conn.write_all(response.as_bytes()).await?;
+    Ok(())
+}
+
+async fn handle(mut client: TcpStream, resolver: &PinnedResolver) -> Result<()> {
+    let (head, rest) = read_head(&mut client).await?;
+    let mut request_line = head.split("\r\n").next().unwrap_or_default().split(' ');
+    let (Some(method), Some(target), Some(version)) = (
+        request_line.next(),
+        request_line.next(),
+        request_line.next(),
+    ) else {
+        return refuse(&mut client, "malformed request line").await;
+    };
+
+    let (host, port, forwarded) = if method.eq_ignore_ascii_case("CONNECT") {
+        let Some((host, port)) = split_host_port(target) else {
+            return refuse(&mut client, "malformed CONNECT target").await;
+        };
+        (host.to_string(), port, None)
+    } else {
+        let url = match Url::parse(target) {
+            Ok(url) if url.scheme() == "http" => url,
+            _ => return refuse(&mut client, "only http:// URLs can be proxied").await,
+        };
+        let (Some(host), Some(port)) = (url.host_str(), url.port_or_known_default()) else {
+            return refuse(&mut client, "request URL has no host").await;
+        };
+        let host = host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_string();
+        let forwarded = origin_form_head(&head, method, &url, version);
+        (host, port, Some(forwarded))
+    };
+
+    let mut upstream = match connect_vetted(resolver, &host, port).await {
+        Ok(upstream) => upstream,
+        Err(e) => return refuse(&mut client, &e.to_string()).await,
+    };
+    match forwarded {
+        Some(head) => upstream.write_all(head.as_bytes()).await?,
+        None => {
+            client
+                .write_all(b"HTTP/1.1 200 Connection Established\r\n\r\n")
+                .await?
+        }
+    }
+    upstream.write_all(&rest).await?;
+    tokio::io::copy_bidirectional(&mut client, &mut upstream).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ip_policy::IpPolicy;
+    use crate::resolver::tests::FakeLookup;
+
+    async fn send(proxy: &SsrfProxy, request: &str) -> String {
+        let mut conn = TcpStream::connect(proxy.addr).await.unwrap();
+        conn.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        conn.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn refuses_private_targets() {
+        let lookup = FakeLookup::with(&[("rebind.test", &[&["127.0.0.1"]])]);
+        let proxy = SsrfProxy::start(PinnedResolver::new(lookup, IpPolicy::default()))
+            .await
+            .unwrap();
+
+        let tunnel = send(
+            &proxy,
+            "CONNECT rebind.test:443 HTTP/1.1\r\nHost: rebind.test:443\r\n\r\n",
+        )
+        .await;
+        assert!(tunnel.starts_with("HTTP/1.1 403"), "{tunnel}");
+        assert!(tunnel.contains("denied addresses"), "{tunnel}");
+
+        let plain = send(
+            &proxy,
+            "GET http://169.254.169.254/latest/meta-data HTTP/1.1\r\n\r\n",
+        )
+        .await;
+        assert!(plain.starts_with("HTTP/1.1 403"), "{plain}");
+        assert!(plain.contains("169.254.0.0/16"), "{plain}");
+
+        let ftp = send(&proxy, "GET ftp://example.com/ HTTP/1.1\r\n\r\n").await;
+        assert!(ftp.contains("only http://"), "{ftp}");
+    }
+
+    #[test]
+    fn rewrites_absolute_form_requests() {
+        let head = "GET http://example.com/a?b=c HTTP/1.1\r\nHost: example.com\r\nProxy-Connection: keep-alive\r\nAccept: */*";
+        let url = Url::parse("http://example.com/a?b=c").unwrap();
+        assert_eq!(
+            origin_form_head(head, "GET", &url, "HTTP/1.1"),
+            "GET /a?b=c HTTP/1.1\r\nHost: example.com\r\nAccept: */*\r\nConnection: close\r\n\r\n"
+        );
+        assert_eq!(split_host_port("[::1]:8443"), Some(("::1", 8443)));
+        assert_eq!(split_host_port("example.com"), None);
+    }
+}